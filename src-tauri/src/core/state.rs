@@ -1,18 +1,25 @@
 use std::sync::{Arc, Mutex};
 use crate::infrastructure::database::Database;
+use crate::features::undo::UndoHistory;
 
 pub struct AppState {
     pub db: Arc<Mutex<Database>>,
+    pub undo_history: Arc<Mutex<UndoHistory>>,
 }
 
 impl AppState {
     pub fn new(db: Database) -> Self {
         Self {
             db: Arc::new(Mutex::new(db)),
+            undo_history: Arc::new(Mutex::new(UndoHistory::default())),
         }
     }
     
     pub fn db(&self) -> Arc<Mutex<Database>> {
         Arc::clone(&self.db)
     }
-}
\ No newline at end of file
+    
+    pub fn undo_history(&self) -> Arc<Mutex<UndoHistory>> {
+        Arc::clone(&self.undo_history)
+    }
+}
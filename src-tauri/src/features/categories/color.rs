@@ -0,0 +1,95 @@
+use serde::Serialize;
+use crate::core::error::{AppError, AppResult};
+
+/// Category palette from color-palettes.html, usable by name instead of a hex value
+pub const PALETTE: [(&str, &str); 8] = [
+    ("personal", "#3b82f6"),
+    ("work", "#f59e0b"),
+    ("projects", "#8b5cf6"),
+    ("ideas", "#ec4899"),
+    ("research", "#06b6d4"),
+    ("important", "#ef4444"),
+    ("draft", "#f97316"),
+    ("archive", "#6b7280"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PaletteColor {
+    pub name: String,
+    pub color: String,
+}
+
+pub fn palette() -> Vec<PaletteColor> {
+    PALETTE.iter()
+        .map(|(name, color)| PaletteColor {
+            name: name.to_string(),
+            color: color.to_string(),
+        })
+        .collect()
+}
+
+/// Resolves a palette name or parses `#RGB`, `#RRGGBB` or `#RRGGBBAA` into
+/// lowercase `#rrggbb` (or `#rrggbbaa` when an alpha channel is given)
+pub fn normalize_color(input: &str) -> AppResult<String> {
+    let input = input.trim();
+
+    if let Some((_, color)) = PALETTE.iter().find(|(name, _)| name.eq_ignore_ascii_case(input)) {
+        return Ok(color.to_string());
+    }
+
+    let invalid = || AppError::new(
+        "VALIDATION_ERROR",
+        format!("Invalid color '{}': use #RGB, #RRGGBB, #RRGGBBAA or a palette name", input),
+    );
+
+    let digits = input.strip_prefix('#').ok_or_else(invalid)?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let digits = digits.to_ascii_lowercase();
+    match digits.len() {
+        3 => Ok(digits.chars().fold(String::from("#"), |mut hex, c| {
+            hex.push(c);
+            hex.push(c);
+            hex
+        })),
+        6 | 8 => Ok(format!("#{}", digits)),
+        _ => Err(invalid()),
+    }
+}
+
+/// The main window background of the default dark theme (`--theme-bg-primary`)
+const BACKDROP: [u8; 3] = [30, 30, 30];
+
+/// Black or white, whichever reads better on `color` by WCAG contrast ratio.
+/// A translucent `#RRGGBBAA` color is first blended over the app background,
+/// since that is what shows through it. Unparseable colors (legacy rows) get
+/// white, matching the dark UI.
+pub fn contrasting_text_color(color: &str) -> String {
+    let rgb = match normalize_color(color) {
+        Ok(hex) => hex,
+        Err(_) => return "#ffffff".to_string(),
+    };
+
+    let component = |index: usize| u8::from_str_radix(&rgb[index..index + 2], 16).unwrap_or(0) as f64 / 255.0;
+    let alpha = if rgb.len() == 9 { component(7) } else { 1.0 };
+
+    let channel = |index: usize| {
+        let backdrop = BACKDROP[index / 2] as f64 / 255.0;
+        let value = alpha * component(index) + (1.0 - alpha) * backdrop;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let luminance = 0.2126 * channel(1) + 0.7152 * channel(3) + 0.0722 * channel(5);
+
+    // Contrast against black is (L + 0.05) / 0.05, against white 1.05 / (L + 0.05)
+    if (luminance + 0.05) / 0.05 >= 1.05 / (luminance + 0.05) {
+        "#000000".to_string()
+    } else {
+        "#ffffff".to_string()
+    }
+}
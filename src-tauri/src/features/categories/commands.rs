@@ -1,7 +1,14 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 use crate::core::AppState;
-use super::models::{Category, CreateCategoryRequest, UpdateCategoryRequest, CategoryPreset};
+use crate::features::sync::watcher::sync_after_change;
+use crate::features::undo::UndoService;
+use crate::features::windows::tray::refresh_tray;
+use super::models::{
+    Category, CreateCategoryRequest, UpdateCategoryRequest, CategoryPreset, CategoryPresetRequest,
+    DeleteStrategy, DeleteCategorySummary,
+};
 use super::service::CategoryService;
+use super::color::{self, PaletteColor};
 
 #[tauri::command]
 pub fn create_category(
@@ -23,6 +30,16 @@ pub fn get_category(
         .map_err(|e| e.message)
 }
 
+#[tauri::command]
+pub fn get_category_by_uuid(
+    state: State<AppState>,
+    uuid: String,
+) -> Result<Option<Category>, String> {
+    let service = CategoryService::new(state.db());
+    service.get_category_by_uuid(&uuid)
+        .map_err(|e| e.message)
+}
+
 #[tauri::command]
 pub fn get_all_categories(
     state: State<AppState>,
@@ -47,23 +64,131 @@ pub fn update_category(
     request: UpdateCategoryRequest,
 ) -> Result<Category, String> {
     let service = CategoryService::new(state.db());
-    service.update_category(request)
+    let undo = UndoService::new(state.db(), state.undo_history());
+    undo.record("Edit category", || service.update_category(request))
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn move_category(
+    state: State<AppState>,
+    id: i64,
+    new_parent_id: Option<i64>,
+    position: Option<usize>,
+) -> Result<Category, String> {
+    let service = CategoryService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    undo.record("Move category", || service.move_category(id, new_parent_id, position))
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn reorder_categories(
+    state: State<AppState>,
+    parent_id: Option<i64>,
+    ordered_ids: Vec<i64>,
+) -> Result<(), String> {
+    let service = CategoryService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    undo.record("Reorder categories", || service.reorder_categories(parent_id, ordered_ids))
         .map_err(|e| e.message)
 }
 
 #[tauri::command]
 pub fn delete_category(
+    app: AppHandle,
+    state: State<AppState>,
+    id: i64,
+    strategy: Option<DeleteStrategy>,
+    dry_run: Option<bool>,
+    confirmed: Option<DeleteCategorySummary>,
+) -> Result<DeleteCategorySummary, String> {
+    let service = CategoryService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    let strategy = strategy.unwrap_or_default();
+    let dry_run = dry_run.unwrap_or(false);
+    let summary = undo.record("Delete category", || service.delete_category(id, strategy, dry_run, confirmed))
+        .map_err(|e| e.message)?;
+    
+    // Deleting notes along with the category can empty the recent list
+    if !summary.dry_run && summary.deleted_notes > 0 {
+        refresh_tray(&app);
+    }
+    if !summary.dry_run {
+        sync_after_change(&app);
+    }
+    
+    Ok(summary)
+}
+
+#[tauri::command]
+pub fn merge_categories(
+    app: AppHandle,
+    state: State<AppState>,
+    source_id: i64,
+    target_id: i64,
+) -> Result<Category, String> {
+    let service = CategoryService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    let category = undo.record("Merge categories", || service.merge_categories(source_id, target_id))
+        .map_err(|e| e.message)?;
+    sync_after_change(&app);
+    Ok(category)
+}
+
+#[tauri::command]
+pub fn get_color_palette() -> Result<Vec<PaletteColor>, String> {
+    Ok(color::palette())
+}
+
+#[tauri::command]
+pub fn get_category_presets(
+    state: State<AppState>,
+) -> Result<Vec<CategoryPreset>, String> {
+    let service = CategoryService::new(state.db());
+    service.get_category_presets()
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn create_category_preset(
+    state: State<AppState>,
+    request: CategoryPresetRequest,
+) -> Result<CategoryPreset, String> {
+    let service = CategoryService::new(state.db());
+    service.create_preset(request)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn update_category_preset(
+    state: State<AppState>,
+    id: i64,
+    request: CategoryPresetRequest,
+) -> Result<CategoryPreset, String> {
+    let service = CategoryService::new(state.db());
+    service.update_preset(id, request)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn delete_category_preset(
     state: State<AppState>,
     id: i64,
 ) -> Result<(), String> {
     let service = CategoryService::new(state.db());
-    service.delete_category(id)
+    service.delete_preset(id)
         .map_err(|e| e.message)
 }
 
 #[tauri::command]
-pub fn get_category_presets() -> Result<Vec<CategoryPreset>, String> {
-    Ok(CategoryPreset::get_default_presets())
+pub fn reorder_category_presets(
+    state: State<AppState>,
+    ordered_ids: Vec<i64>,
+) -> Result<(), String> {
+    let service = CategoryService::new(state.db());
+    service.reorder_presets(ordered_ids)
+        .map_err(|e| e.message)
 }
 
 #[tauri::command]
@@ -74,4 +199,15 @@ pub fn create_category_from_preset(
     let service = CategoryService::new(state.db());
     service.create_from_preset(&preset_name)
         .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn create_preset_set(
+    state: State<AppState>,
+    preset_names: Option<Vec<String>>,
+) -> Result<Vec<Category>, String> {
+    let service = CategoryService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    undo.record("Create preset categories", || service.create_preset_set(preset_names))
+        .map_err(|e| e.message)
 }
\ No newline at end of file
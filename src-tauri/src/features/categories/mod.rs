@@ -1,4 +1,5 @@
 pub mod models;
+pub mod color;
 pub mod repository;
 pub mod service;
 pub mod commands;
@@ -4,11 +4,18 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Category {
     pub id: Option<i64>,
+    pub uuid: String,
     pub name: String,
     pub color: String,
+    pub text_color: String, // Black or white, whichever is readable on `color`
     pub parent_id: Option<i64>,
+    pub sort_order: i64, // Position among siblings
+    pub icon: Option<String>,
+    pub description: Option<String>,
     pub created_at: DateTime<Utc>,
     pub subcategories: Option<Vec<Category>>, // Populated when needed
+    pub note_count: Option<i64>, // Notes filed directly in this category, when needed
+    pub total_note_count: Option<i64>, // Including all subcategories, when needed
 }
 
 #[derive(Debug, Deserialize)]
@@ -16,6 +23,8 @@ pub struct CreateCategoryRequest {
     pub name: String,
     pub color: String,
     pub parent_id: Option<i64>,
+    pub icon: Option<String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -24,14 +33,56 @@ pub struct UpdateCategoryRequest {
     pub name: String,
     pub color: String,
     pub parent_id: Option<i64>,
+    pub icon: Option<String>,
+    pub description: Option<String>,
 }
 
-// Predefined category presets from color-palettes.html research
+/// What happens to the notes and subcategories of a deleted category
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeleteStrategy {
+    /// Delete the whole subtree and leave its notes uncategorized
+    #[default]
+    Orphan,
+    /// Hand notes and direct subcategories over to the parent (top level if none)
+    MoveToParent,
+    /// Hand notes and direct subcategories over to another category
+    Reassign { target_id: i64 },
+    /// Delete the whole subtree together with its notes
+    DeleteNotes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteCategorySummary {
+    pub dry_run: bool,
+    pub destination_id: Option<i64>, // Where notes and subcategories go, if they move
+    pub deleted_categories: Vec<i64>,
+    pub moved_categories: Vec<i64>,
+    pub moved_notes: i64,
+    pub orphaned_notes: i64,
+    pub deleted_notes: i64,
+    pub conflicts: Vec<String>, // Subcategory names already taken at the destination
+}
+
+// Predefined category presets from color-palettes.html research,
+// followed by the user's own presets stored in `category_presets`
 #[derive(Debug, Clone, Serialize)]
 pub struct CategoryPreset {
+    pub id: Option<i64>, // None for built-in presets
+    pub name: String,
+    pub color: String,
+    pub description: String,
+    pub icon: String,
+    pub is_builtin: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryPresetRequest {
     pub name: String,
     pub color: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub icon: String,
 }
 
@@ -39,40 +90,52 @@ impl CategoryPreset {
     pub fn get_default_presets() -> Vec<CategoryPreset> {
         vec![
             CategoryPreset {
+                id: None,
                 name: "Projects".to_string(),
                 color: "#8B5CF6".to_string(),
                 description: "Creative projects and long-term planning".to_string(),
                 icon: "material-symbols:folder-open".to_string(),
+                is_builtin: true,
             },
             CategoryPreset {
+                id: None,
                 name: "Ideas".to_string(),
                 color: "#EC4899".to_string(),
                 description: "Brainstorming and creative inspiration".to_string(),
                 icon: "material-symbols:lightbulb".to_string(),
+                is_builtin: true,
             },
             CategoryPreset {
+                id: None,
                 name: "Research".to_string(),
                 color: "#06B6D4".to_string(),
                 description: "Research notes and data collection".to_string(),
                 icon: "material-symbols:science".to_string(),
+                is_builtin: true,
             },
             CategoryPreset {
+                id: None,
                 name: "Important".to_string(),
                 color: "#EF4444".to_string(),
                 description: "Urgent and critical information".to_string(),
                 icon: "material-symbols:priority-high".to_string(),
+                is_builtin: true,
             },
             CategoryPreset {
+                id: None,
                 name: "Draft".to_string(),
                 color: "#F97316".to_string(),
                 description: "Work-in-progress and unfinished thoughts".to_string(),
                 icon: "material-symbols:edit-note".to_string(),
+                is_builtin: true,
             },
             CategoryPreset {
+                id: None,
                 name: "Archive".to_string(),
                 color: "#6B7280".to_string(),
                 description: "Completed or stored items".to_string(),
                 icon: "material-symbols:archive".to_string(),
+                is_builtin: true,
             },
         ]
     }
@@ -1,9 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
-use rusqlite::{params, Row};
+use rusqlite::{params, OptionalExtension, Row};
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
 use crate::core::error::{AppResult, AppError};
 use crate::infrastructure::database::Database;
-use super::models::{Category, CreateCategoryRequest, UpdateCategoryRequest};
+use crate::features::journal::{ChangeJournal, ChangeEntity};
+use super::color::contrasting_text_color;
+use super::models::{
+    Category, CreateCategoryRequest, UpdateCategoryRequest, DeleteStrategy, DeleteCategorySummary,
+    CategoryPreset, CategoryPresetRequest,
+};
 
 pub struct CategoryRepository {
     db: Arc<Mutex<Database>>,
@@ -15,23 +22,74 @@ impl CategoryRepository {
     }
 
     pub fn create_category(&self, request: CreateCategoryRequest) -> AppResult<Category> {
+        Ok(self.create_categories(vec![request])?.remove(0))
+    }
+
+    /// Creates several categories in a single transaction
+    pub fn create_categories(&self, requests: Vec<CreateCategoryRequest>) -> AppResult<Vec<Category>> {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let mut categories = Vec::with_capacity(requests.len());
+        for request in requests {
+            categories.push(Self::insert_category(&tx, &journal, request)?);
+        }
+        
+        tx.commit()?;
+        Ok(categories)
+    }
+
+    fn insert_category(
+        conn: &rusqlite::Connection,
+        journal: &ChangeJournal,
+        request: CreateCategoryRequest,
+    ) -> AppResult<Category> {
         let now = Utc::now();
+        let uuid = Uuid::new_v4().to_string();
+        
+        // New categories go after their existing siblings
+        let sort_order: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE parent_id IS ?1",
+            params![request.parent_id],
+            |row| row.get(0)
+        )?;
         
-        let _id = conn.execute(
-            "INSERT INTO categories (name, color, parent_id, created_at) VALUES (?1, ?2, ?3, ?4)",
-            params![request.name, request.color, request.parent_id, now.to_rfc3339()],
+        conn.execute(
+            "INSERT INTO categories (uuid, name, color, parent_id, sort_order, icon, description, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                uuid,
+                request.name,
+                request.color,
+                request.parent_id,
+                sort_order,
+                request.icon,
+                request.description,
+                now.to_rfc3339()
+            ],
         ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
         
+        let id = conn.last_insert_rowid();
+        journal.record(ChangeEntity::Category, id, None)?;
+        
         Ok(Category {
-            id: Some(conn.last_insert_rowid()),
+            id: Some(id),
+            uuid,
             name: request.name,
+            text_color: contrasting_text_color(&request.color),
             color: request.color,
             parent_id: request.parent_id,
+            sort_order,
+            icon: request.icon,
+            description: request.description,
             created_at: now,
             subcategories: None,
+            note_count: None,
+            total_note_count: None,
         })
     }
 
@@ -40,12 +98,27 @@ impl CategoryRepository {
         let conn = db.connection();
         
         let mut stmt = conn.prepare(
-            "SELECT id, name, color, parent_id, created_at FROM categories WHERE id = ?1"
+            "SELECT id, uuid, name, color, parent_id, created_at, sort_order, icon, description FROM categories WHERE id = ?1"
         ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
         
-        let result = stmt.query_row(params![id], |row| {
-            Ok(self.map_row(row)?)
-        });
+        let result = stmt.query_row(params![id], |row| self.map_row(row));
+        
+        match result {
+            Ok(category) => Ok(Some(category)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::new("DATABASE_ERROR", e.to_string())),
+        }
+    }
+
+    pub fn get_category_by_uuid(&self, uuid: &str) -> AppResult<Option<Category>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, uuid, name, color, parent_id, created_at, sort_order, icon, description FROM categories WHERE uuid = ?1"
+        ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+        
+        let result = stmt.query_row(params![uuid], |row| self.map_row(row));
         
         match result {
             Ok(category) => Ok(Some(category)),
@@ -59,12 +132,10 @@ impl CategoryRepository {
         let conn = db.connection();
         
         let mut stmt = conn.prepare(
-            "SELECT id, name, color, parent_id, created_at FROM categories ORDER BY name ASC"
+            "SELECT id, uuid, name, color, parent_id, created_at, sort_order, icon, description FROM categories ORDER BY sort_order ASC, name ASC"
         ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
         
-        let category_iter = stmt.query_map([], |row| {
-            Ok(self.map_row(row)?)
-        }).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+        let category_iter = stmt.query_map([], |row| self.map_row(row)).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
         
         let mut categories = Vec::new();
         for category in category_iter {
@@ -75,30 +146,294 @@ impl CategoryRepository {
     }
 
     pub fn update_category(&self, request: UpdateCategoryRequest) -> AppResult<Category> {
-        let db = self.db.lock().unwrap();
-        let conn = db.connection();
-        
-        conn.execute(
-            "UPDATE categories SET name = ?1, color = ?2, parent_id = ?3 WHERE id = ?4",
-            params![request.name, request.color, request.parent_id, request.id],
-        ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+        {
+            let db = self.db.lock().unwrap();
+            let conn = db.connection();
+            
+            let tx = conn.unchecked_transaction()
+                .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+            let journal = ChangeJournal::new(&tx, db.device_id());
+            let before = journal.snapshot(ChangeEntity::Category, request.id)?;
+            
+            // A category reparented here lands after its new siblings
+            tx.execute(
+                "UPDATE categories SET name = ?1, color = ?2, parent_id = ?3, icon = ?5, description = ?6,
+                    sort_order = CASE WHEN parent_id IS ?3 THEN sort_order
+                        ELSE (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE parent_id IS ?3) END
+                 WHERE id = ?4",
+                params![request.name, request.color, request.parent_id, request.id, request.icon, request.description],
+            ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+            
+            journal.record(ChangeEntity::Category, request.id, before)?;
+            tx.commit()?;
+        }
         
         self.get_category(request.id)?
             .ok_or_else(|| AppError::new("NOT_FOUND", "Category not found after update"))
     }
 
-    pub fn delete_category(&self, id: i64) -> AppResult<()> {
+    /// Reparents a category and places it at `position` among its new siblings
+    /// (appended when `None`). Siblings are renumbered to keep the order dense.
+    pub fn move_category(
+        &self,
+        id: i64,
+        new_parent_id: Option<i64>,
+        position: Option<usize>,
+    ) -> AppResult<Category> {
+        {
+            let db = self.db.lock().unwrap();
+            let conn = db.connection();
+            
+            let tx = conn.unchecked_transaction()
+                .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+            let journal = ChangeJournal::new(&tx, db.device_id());
+            
+            let mut siblings = tx.prepare(
+                "SELECT id FROM categories WHERE parent_id IS ?1 AND id != ?2 ORDER BY sort_order ASC, name ASC"
+            )?
+                .query_map(params![new_parent_id, id], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            
+            let position = position.unwrap_or(siblings.len()).min(siblings.len());
+            siblings.insert(position, id);
+            
+            let before = journal.snapshot(ChangeEntity::Category, id)?;
+            tx.execute(
+                "UPDATE categories SET parent_id = ?1 WHERE id = ?2",
+                params![new_parent_id, id],
+            ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+            journal.record(ChangeEntity::Category, id, before)?;
+            
+            for (index, sibling_id) in siblings.iter().enumerate() {
+                let before = journal.snapshot(ChangeEntity::Category, *sibling_id)?;
+                tx.execute(
+                    "UPDATE categories SET sort_order = ?1 WHERE id = ?2 AND sort_order != ?1",
+                    params![index as i64, sibling_id],
+                )?;
+                journal.record(ChangeEntity::Category, *sibling_id, before)?;
+            }
+            
+            tx.commit()?;
+        }
+        
+        self.get_category(id)?
+            .ok_or_else(|| AppError::new("NOT_FOUND", "Category not found after move"))
+    }
+
+    /// Applies a new sibling order under `parent_id`. Children missing from
+    /// `ordered_ids` keep their relative order after the listed ones.
+    pub fn reorder_categories(&self, parent_id: Option<i64>, ordered_ids: &[i64]) -> AppResult<()> {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         
         let tx = conn.unchecked_transaction()
             .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
         
-        // Set category_id to NULL for all notes with this category
-        tx.execute(
-            "UPDATE notes SET category_id = NULL WHERE category_id = ?1",
+        let siblings = tx.prepare(
+            "SELECT id FROM categories WHERE parent_id IS ?1 ORDER BY sort_order ASC, name ASC"
+        )?
+            .query_map(params![parent_id], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        if let Some(stray) = ordered_ids.iter().find(|id| !siblings.contains(id)) {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                format!("Category {} is not a child of the given parent", stray),
+            ));
+        }
+        
+        let remaining = siblings.into_iter().filter(|id| !ordered_ids.contains(id));
+        for (index, category_id) in ordered_ids.iter().copied().chain(remaining).enumerate() {
+            let before = journal.snapshot(ChangeEntity::Category, category_id)?;
+            tx.execute(
+                "UPDATE categories SET sort_order = ?1 WHERE id = ?2 AND sort_order != ?1",
+                params![index as i64, category_id],
+            )?;
+            journal.record(ChangeEntity::Category, category_id, before)?;
+        }
+        
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// True when `id` is `ancestor_id` itself or sits somewhere below it
+    pub fn is_descendant(&self, id: i64, ancestor_id: i64) -> AppResult<bool> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut current = Some(id);
+        let mut visited = HashSet::new();
+        
+        while let Some(category_id) = current {
+            if category_id == ancestor_id {
+                return Ok(true);
+            }
+            // Stop on pre-existing loops instead of walking them forever
+            if !visited.insert(category_id) {
+                break;
+            }
+            current = conn.query_row(
+                "SELECT parent_id FROM categories WHERE id = ?1",
+                params![category_id],
+                |row| row.get::<_, Option<i64>>(0)
+            ).optional()?.flatten();
+        }
+        
+        Ok(false)
+    }
+
+    /// Works out what deleting `id` with `strategy` would touch, without changing anything
+    pub fn plan_delete(&self, id: i64, strategy: &DeleteStrategy) -> AppResult<DeleteCategorySummary> {
+        let db = self.db.lock().unwrap();
+        Self::build_plan(db.connection(), id, strategy)
+    }
+    
+    fn build_plan(conn: &rusqlite::Connection, id: i64, strategy: &DeleteStrategy) -> AppResult<DeleteCategorySummary> {
+        let parent_id: Option<i64> = conn.query_row(
+            "SELECT parent_id FROM categories WHERE id = ?1",
             params![id],
+            |row| row.get(0)
+        )?;
+        let subtree = Self::collect_subtree(conn, id)?;
+        let count_notes = |category_ids: &[i64]| -> AppResult<i64> {
+            let mut total = 0;
+            for category_id in category_ids {
+                total += conn.query_row(
+                    "SELECT COUNT(*) FROM notes WHERE category_id = ?1",
+                    params![category_id],
+                    |row| row.get::<_, i64>(0)
+                )?;
+            }
+            Ok(total)
+        };
+        
+        let mut summary = DeleteCategorySummary {
+            dry_run: true,
+            destination_id: None,
+            deleted_categories: vec![],
+            moved_categories: vec![],
+            moved_notes: 0,
+            orphaned_notes: 0,
+            deleted_notes: 0,
+            conflicts: vec![],
+        };
+        
+        match strategy {
+            DeleteStrategy::Orphan => {
+                summary.orphaned_notes = count_notes(&subtree)?;
+                summary.deleted_categories = subtree;
+            }
+            DeleteStrategy::DeleteNotes => {
+                summary.deleted_notes = count_notes(&subtree)?;
+                summary.deleted_categories = subtree;
+            }
+            DeleteStrategy::MoveToParent | DeleteStrategy::Reassign { .. } => {
+                let destination_id = match strategy {
+                    DeleteStrategy::Reassign { target_id } => Some(*target_id),
+                    _ => parent_id,
+                };
+                
+                let children = conn.prepare("SELECT id, name FROM categories WHERE parent_id = ?1 ORDER BY sort_order ASC, name ASC")?
+                    .query_map(params![id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                
+                for (child_id, name) in &children {
+                    let taken: bool = conn.query_row(
+                        "SELECT EXISTS(SELECT 1 FROM categories WHERE parent_id IS ?1 AND name = ?2 AND id != ?3)",
+                        params![destination_id, name, child_id],
+                        |row| row.get(0)
+                    )?;
+                    if taken {
+                        summary.conflicts.push(name.clone());
+                    }
+                }
+                
+                summary.destination_id = destination_id;
+                summary.moved_notes = count_notes(&[id])?;
+                summary.moved_categories = children.into_iter().map(|(child_id, _)| child_id).collect();
+                summary.deleted_categories = vec![id];
+            }
+        }
+        
+        Ok(summary)
+    }
+
+    /// Deletes the category in a single transaction, planning it again inside
+    /// that transaction. Fails when the plan has conflicts, or no longer matches
+    /// the `confirmed` one the user was shown.
+    pub fn delete_category(
+        &self,
+        id: i64,
+        strategy: &DeleteStrategy,
+        confirmed: Option<&DeleteCategorySummary>,
+    ) -> AppResult<DeleteCategorySummary> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let mut plan = Self::build_plan(&tx, id, strategy)?;
+        if !plan.conflicts.is_empty() {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                format!("Categories named {} already exist at the destination", plan.conflicts.join(", ")),
+            ));
+        }
+        if let Some(confirmed) = confirmed {
+            let confirmed = DeleteCategorySummary { dry_run: plan.dry_run, ..confirmed.clone() };
+            if confirmed != plan {
+                return Err(AppError::new(
+                    "PLAN_CHANGED",
+                    "The category changed since the deletion was previewed; review it again",
+                ));
+            }
+        }
+        
+        // Notes filed anywhere in the deleted categories are moved, cleared or removed
+        for category_id in &plan.deleted_categories {
+            let note_ids = tx.prepare("SELECT id FROM notes WHERE category_id = ?1")?
+                .query_map(params![category_id], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            
+            for note_id in note_ids {
+                let before = journal.snapshot(ChangeEntity::Note, note_id)?;
+                if matches!(strategy, DeleteStrategy::DeleteNotes) {
+                    tx.execute("DELETE FROM notes WHERE id = ?1", params![note_id])?;
+                } else {
+                    // Manual positions belong to the old category
+                    tx.execute(
+                        "UPDATE notes SET category_id = ?1, sort_order = NULL WHERE id = ?2",
+                        params![plan.destination_id, note_id],
+                    )?;
+                }
+                journal.record(ChangeEntity::Note, note_id, before)?;
+            }
+        }
+        
+        // Surviving subcategories go after the destination's own children
+        let first_order: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE parent_id IS ?1",
+            params![plan.destination_id],
+            |row| row.get(0)
         )?;
+        for (sort_order, child_id) in (first_order..).zip(&plan.moved_categories) {
+            let before = journal.snapshot(ChangeEntity::Category, *child_id)?;
+            tx.execute(
+                "UPDATE categories SET parent_id = ?1, sort_order = ?2 WHERE id = ?3",
+                params![plan.destination_id, sort_order, child_id],
+            ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+            journal.record(ChangeEntity::Category, *child_id, before)?;
+        }
+        
+        // Subcategories go with their parent (ON DELETE CASCADE), so snapshot
+        // the remaining subtree to journal every row the delete touches
+        let mut snapshots = Vec::new();
+        for category_id in &plan.deleted_categories {
+            snapshots.push((*category_id, journal.snapshot(ChangeEntity::Category, *category_id)?));
+        }
         
         // Delete the category
         tx.execute(
@@ -106,30 +441,282 @@ impl CategoryRepository {
             params![id],
         )?;
         
+        // Deepest first, so replaying the journal backwards recreates parents before children
+        for (category_id, before) in snapshots.into_iter().rev() {
+            journal.record(ChangeEntity::Category, category_id, before)?;
+        }
+        
+        tx.commit()?;
+        plan.dry_run = false;
+        Ok(plan)
+    }
+
+    /// Folds `source` into `target` in one transaction: notes and subcategories
+    /// move over, same-named subcategories are merged recursively, and `source`
+    /// is deleted.
+    pub fn merge_categories(&self, source_id: i64, target_id: i64) -> AppResult<Category> {
+        {
+            let db = self.db.lock().unwrap();
+            let conn = db.connection();
+            
+            let tx = conn.unchecked_transaction()
+                .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+            let journal = ChangeJournal::new(&tx, db.device_id());
+            
+            Self::merge_into(&tx, &journal, source_id, target_id)?;
+            
+            tx.commit()?;
+        }
+        
+        self.get_category(target_id)?
+            .ok_or_else(|| AppError::new("NOT_FOUND", "Category not found after merge"))
+    }
+
+    fn merge_into(
+        conn: &rusqlite::Connection,
+        journal: &ChangeJournal,
+        source_id: i64,
+        target_id: i64,
+    ) -> AppResult<()> {
+        let note_ids = conn.prepare("SELECT id FROM notes WHERE category_id = ?1")?
+            .query_map(params![source_id], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        for note_id in note_ids {
+            let before = journal.snapshot(ChangeEntity::Note, note_id)?;
+            conn.execute(
+                "UPDATE notes SET category_id = ?1, sort_order = NULL WHERE id = ?2",
+                params![target_id, note_id],
+            )?;
+            journal.record(ChangeEntity::Note, note_id, before)?;
+        }
+        
+        let children = conn.prepare("SELECT id, name FROM categories WHERE parent_id = ?1 ORDER BY sort_order ASC, name ASC")?
+            .query_map(params![source_id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        for (child_id, name) in children {
+            let namesake: Option<i64> = conn.query_row(
+                "SELECT id FROM categories WHERE parent_id = ?1 AND name = ?2 AND id != ?3",
+                params![target_id, name, source_id],
+                |row| row.get(0)
+            ).optional()?;
+            
+            match namesake {
+                Some(existing_id) => Self::merge_into(conn, journal, child_id, existing_id)?,
+                None => {
+                    let before = journal.snapshot(ChangeEntity::Category, child_id)?;
+                    conn.execute(
+                        "UPDATE categories SET parent_id = ?1,
+                            sort_order = (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM categories WHERE parent_id = ?1)
+                         WHERE id = ?2",
+                        params![target_id, child_id],
+                    )?;
+                    journal.record(ChangeEntity::Category, child_id, before)?;
+                }
+            }
+        }
+        
+        // Everything has moved out, so the delete cascades to nothing
+        let before = journal.snapshot(ChangeEntity::Category, source_id)?;
+        conn.execute("DELETE FROM categories WHERE id = ?1", params![source_id])?;
+        journal.record(ChangeEntity::Category, source_id, before)?;
+        
+        Ok(())
+    }
+
+    pub fn get_user_presets(&self) -> AppResult<Vec<CategoryPreset>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, name, color, description, icon FROM category_presets ORDER BY sort_order ASC, name ASC"
+        )?;
+        
+        let presets = stmt.query_map([], |row| {
+            Ok(CategoryPreset {
+                id: Some(row.get(0)?),
+                name: row.get(1)?,
+                color: row.get(2)?,
+                description: row.get(3)?,
+                icon: row.get(4)?,
+                is_builtin: false,
+            })
+        })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(presets)
+    }
+
+    pub fn create_preset(&self, request: CategoryPresetRequest) -> AppResult<CategoryPreset> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "INSERT INTO category_presets (name, color, description, icon, sort_order, created_at)
+             VALUES (?1, ?2, ?3, ?4, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM category_presets), ?5)",
+            params![request.name, request.color, request.description, request.icon, Utc::now().to_rfc3339()],
+        ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+        
+        Ok(CategoryPreset {
+            id: Some(conn.last_insert_rowid()),
+            name: request.name,
+            color: request.color,
+            description: request.description,
+            icon: request.icon,
+            is_builtin: false,
+        })
+    }
+
+    pub fn update_preset(&self, id: i64, request: CategoryPresetRequest) -> AppResult<CategoryPreset> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let updated = conn.execute(
+            "UPDATE category_presets SET name = ?1, color = ?2, description = ?3, icon = ?4 WHERE id = ?5",
+            params![request.name, request.color, request.description, request.icon, id],
+        ).map_err(|e| AppError::new("DATABASE_ERROR", e.to_string()))?;
+        
+        if updated == 0 {
+            return Err(AppError::new("NOT_FOUND", "Preset not found"));
+        }
+        
+        Ok(CategoryPreset {
+            id: Some(id),
+            name: request.name,
+            color: request.color,
+            description: request.description,
+            icon: request.icon,
+            is_builtin: false,
+        })
+    }
+
+    pub fn delete_preset(&self, id: i64) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute("DELETE FROM category_presets WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Presets left out of `ordered_ids` keep their relative order after the listed ones
+    pub fn reorder_presets(&self, ordered_ids: &[i64]) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        
+        let preset_ids = tx.prepare("SELECT id FROM category_presets ORDER BY sort_order ASC, name ASC")?
+            .query_map([], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        if let Some(stray) = ordered_ids.iter().find(|id| !preset_ids.contains(id)) {
+            return Err(AppError::new("NOT_FOUND", format!("Preset {} not found", stray)));
+        }
+        
+        let remaining = preset_ids.into_iter().filter(|id| !ordered_ids.contains(id));
+        for (index, preset_id) in ordered_ids.iter().copied().chain(remaining).enumerate() {
+            tx.execute(
+                "UPDATE category_presets SET sort_order = ?1 WHERE id = ?2",
+                params![index as i64, preset_id],
+            )?;
+        }
+        
         tx.commit()?;
         Ok(())
     }
 
+    /// Returns `root` followed by all its descendants, parents before children
+    fn collect_subtree(conn: &rusqlite::Connection, root: i64) -> AppResult<Vec<i64>> {
+        let mut stmt = conn.prepare("SELECT id FROM categories WHERE parent_id = ?1")?;
+        let mut subtree = vec![root];
+        let mut index = 0;
+        
+        while index < subtree.len() {
+            let children = stmt.query_map(params![subtree[index]], |row| row.get::<_, i64>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for child in children {
+                if !subtree.contains(&child) {
+                    subtree.push(child);
+                }
+            }
+            index += 1;
+        }
+        
+        Ok(subtree)
+    }
+
     fn map_row(&self, row: &Row) -> rusqlite::Result<Category> {
-        let created_at_str: String = row.get(4)?;
+        let color: String = row.get(3)?;
+        let created_at_str: String = row.get(5)?;
         let created_at = DateTime::parse_from_rfc3339(&created_at_str)
-            .map_err(|_e| rusqlite::Error::InvalidColumnType(4, "created_at".to_string(), rusqlite::types::Type::Text))?
+            .map_err(|_e| rusqlite::Error::InvalidColumnType(5, "created_at".to_string(), rusqlite::types::Type::Text))?
             .with_timezone(&Utc);
         
         Ok(Category {
             id: Some(row.get(0)?),
-            name: row.get(1)?,
-            color: row.get(2)?,
-            parent_id: row.get(3)?,
+            uuid: row.get(1)?,
+            name: row.get(2)?,
+            text_color: contrasting_text_color(&color),
+            color,
+            parent_id: row.get(4)?,
+            sort_order: row.get(6)?,
+            icon: row.get(7)?,
+            description: row.get(8)?,
             created_at,
             subcategories: None,
+            note_count: None,
+            total_note_count: None,
         })
     }
     
+    /// Direct and recursive note counts for every category, keyed by id
+    pub fn get_note_counts(&self) -> AppResult<HashMap<i64, (i64, i64)>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        // `tree` pairs each category with itself and every descendant;
+        // UNION (not UNION ALL) keeps a corrupted parent loop from recursing forever
+        let mut stmt = conn.prepare(
+            "WITH RECURSIVE tree(root_id, id) AS (
+                SELECT id, id FROM categories
+                UNION
+                SELECT tree.root_id, c.id FROM categories c JOIN tree ON c.parent_id = tree.id
+            ),
+            direct(category_id, note_count) AS (
+                SELECT category_id, COUNT(*) FROM notes WHERE category_id IS NOT NULL GROUP BY category_id
+            )
+            SELECT tree.root_id,
+                   COALESCE(SUM(CASE WHEN tree.id = tree.root_id THEN direct.note_count END), 0),
+                   COALESCE(SUM(direct.note_count), 0)
+            FROM tree LEFT JOIN direct ON direct.category_id = tree.id
+            GROUP BY tree.root_id"
+        )?;
+        
+        let counts = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, (row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)))
+        })?
+            .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+        
+        Ok(counts)
+    }
+
     pub fn get_hierarchical_categories(&self) -> AppResult<Vec<Category>> {
-        let all_categories = self.get_all_categories()?;
+        let mut all_categories = self.get_all_categories()?;
+        let counts = self.get_note_counts()?;
+        
+        for category in &mut all_categories {
+            let (direct, total) = category.id
+                .and_then(|id| counts.get(&id).copied())
+                .unwrap_or((0, 0));
+            category.note_count = Some(direct);
+            category.total_note_count = Some(total);
+        }
+        
         let mut root_categories = Vec::new();
-        let mut category_map = std::collections::HashMap::new();
+        let mut category_map = HashMap::new();
+        let mut visited = HashSet::new();
         
         // Create a map of all categories
         for category in &all_categories {
@@ -138,38 +725,62 @@ impl CategoryRepository {
             }
         }
         
-        // Build hierarchy
-        for category in all_categories {
-            if category.parent_id.is_none() {
-                // This is a root category
-                let mut root_cat = category.clone();
-                root_cat.subcategories = Some(self.get_subcategories(category.id.unwrap(), &category_map)?);
-                root_categories.push(root_cat);
+        // Build hierarchy from the roots, including categories whose parent is gone
+        for category in &all_categories {
+            let is_root = category.parent_id
+                .is_none_or(|parent_id| !category_map.contains_key(&parent_id));
+            if is_root {
+                root_categories.push(self.build_tree(category, &category_map, &mut visited));
+            }
+        }
+        
+        // Categories caught in a parent loop are unreachable from any root;
+        // surface them at the top level rather than dropping them
+        for category in &all_categories {
+            if category.id.is_some_and(|id| !visited.contains(&id)) {
+                root_categories.push(self.build_tree(category, &category_map, &mut visited));
             }
         }
         
+        Self::sort_siblings(&mut root_categories);
         Ok(root_categories)
     }
     
+    fn build_tree(
+        &self,
+        category: &Category,
+        category_map: &HashMap<i64, Category>,
+        visited: &mut HashSet<i64>,
+    ) -> Category {
+        let mut node = category.clone();
+        if let Some(id) = node.id {
+            visited.insert(id);
+            node.subcategories = Some(self.get_subcategories(id, category_map, visited));
+        }
+        node
+    }
+    
     fn get_subcategories(
         &self, 
         parent_id: i64, 
-        category_map: &std::collections::HashMap<i64, Category>
-    ) -> AppResult<Vec<Category>> {
+        category_map: &HashMap<i64, Category>,
+        visited: &mut HashSet<i64>,
+    ) -> Vec<Category> {
         let mut subcategories = Vec::new();
         
-        for (_, category) in category_map {
-            if category.parent_id == Some(parent_id) {
-                let mut subcat = category.clone();
-                if let Some(sub_id) = subcat.id {
-                    subcat.subcategories = Some(self.get_subcategories(sub_id, category_map)?);
-                }
-                subcategories.push(subcat);
+        for category in category_map.values() {
+            let unvisited = category.id.is_some_and(|id| !visited.contains(&id));
+            if category.parent_id == Some(parent_id) && unvisited {
+                subcategories.push(self.build_tree(category, category_map, visited));
             }
         }
         
-        // Sort by name
-        subcategories.sort_by(|a, b| a.name.cmp(&b.name));
-        Ok(subcategories)
+        Self::sort_siblings(&mut subcategories);
+        subcategories
+    }
+    
+    // Manual order first, name for categories that were never reordered
+    fn sort_siblings(categories: &mut [Category]) {
+        categories.sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.name.cmp(&b.name)));
     }
 }
\ No newline at end of file
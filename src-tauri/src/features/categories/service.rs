@@ -1,8 +1,12 @@
 use std::sync::{Arc, Mutex};
 use crate::core::error::{AppResult, AppError};
 use crate::infrastructure::database::Database;
-use super::models::{Category, CreateCategoryRequest, UpdateCategoryRequest, CategoryPreset};
+use super::models::{
+    Category, CreateCategoryRequest, UpdateCategoryRequest, CategoryPreset, CategoryPresetRequest,
+    DeleteStrategy, DeleteCategorySummary,
+};
 use super::repository::CategoryRepository;
+use super::color::normalize_color;
 
 pub struct CategoryService {
     repository: CategoryRepository,
@@ -15,16 +19,14 @@ impl CategoryService {
         }
     }
 
-    pub fn create_category(&self, request: CreateCategoryRequest) -> AppResult<Category> {
+    pub fn create_category(&self, mut request: CreateCategoryRequest) -> AppResult<Category> {
         // Validate category name
         if request.name.trim().is_empty() {
             return Err(AppError::new("VALIDATION_ERROR", "Category name cannot be empty"));
         }
         
-        // Validate color format (basic hex validation)
-        if !request.color.starts_with('#') || request.color.len() != 7 {
-            return Err(AppError::new("VALIDATION_ERROR", "Color must be in hex format (#RRGGBB)"));
-        }
+        // Validate and normalize color (hex or palette name)
+        request.color = normalize_color(&request.color)?;
         
         // Validate parent exists if provided
         if let Some(parent_id) = request.parent_id {
@@ -40,6 +42,10 @@ impl CategoryService {
         self.repository.get_category(id)
     }
 
+    pub fn get_category_by_uuid(&self, uuid: &str) -> AppResult<Option<Category>> {
+        self.repository.get_category_by_uuid(uuid)
+    }
+
     pub fn get_all_categories(&self) -> AppResult<Vec<Category>> {
         self.repository.get_all_categories()
     }
@@ -48,38 +54,218 @@ impl CategoryService {
         self.repository.get_hierarchical_categories()
     }
 
-    pub fn update_category(&self, request: UpdateCategoryRequest) -> AppResult<Category> {
+    pub fn update_category(&self, mut request: UpdateCategoryRequest) -> AppResult<Category> {
         // Validate category name
         if request.name.trim().is_empty() {
             return Err(AppError::new("VALIDATION_ERROR", "Category name cannot be empty"));
         }
         
-        // Validate color format
-        if !request.color.starts_with('#') || request.color.len() != 7 {
-            return Err(AppError::new("VALIDATION_ERROR", "Color must be in hex format (#RRGGBB)"));
+        // Validate and normalize color (hex or palette name)
+        request.color = normalize_color(&request.color)?;
+        
+        if self.repository.get_category(request.id)?.is_none() {
+            return Err(AppError::new("NOT_FOUND", "Category not found"));
         }
+        self.validate_parent(request.id, request.parent_id)?;
         
         self.repository.update_category(request)
     }
 
-    pub fn delete_category(&self, id: i64) -> AppResult<()> {
-        self.repository.delete_category(id)
+    pub fn move_category(
+        &self,
+        id: i64,
+        new_parent_id: Option<i64>,
+        position: Option<usize>,
+    ) -> AppResult<Category> {
+        if self.repository.get_category(id)?.is_none() {
+            return Err(AppError::new("NOT_FOUND", "Category not found"));
+        }
+        self.validate_parent(id, new_parent_id)?;
+        
+        self.repository.move_category(id, new_parent_id, position)
+    }
+
+    pub fn reorder_categories(&self, parent_id: Option<i64>, ordered_ids: Vec<i64>) -> AppResult<()> {
+        let mut seen = std::collections::HashSet::new();
+        if !ordered_ids.iter().all(|id| seen.insert(*id)) {
+            return Err(AppError::new("VALIDATION_ERROR", "Category order contains duplicate ids"));
+        }
+        self.repository.reorder_categories(parent_id, &ordered_ids)
+    }
+
+    /// Rejects parents that don't exist or would put the category inside its own subtree
+    fn validate_parent(&self, id: i64, parent_id: Option<i64>) -> AppResult<()> {
+        let parent_id = match parent_id {
+            Some(parent_id) => parent_id,
+            None => return Ok(()),
+        };
+        
+        if parent_id == id {
+            return Err(AppError::new("VALIDATION_ERROR", "A category cannot be its own parent"));
+        }
+        
+        if self.repository.get_category(parent_id)?.is_none() {
+            return Err(AppError::new("VALIDATION_ERROR", "Parent category does not exist"));
+        }
+        
+        if self.repository.is_descendant(parent_id, id)? {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                "A category cannot be moved under one of its own subcategories",
+            ));
+        }
+        
+        Ok(())
     }
 
-    pub fn get_category_presets(&self) -> Vec<CategoryPreset> {
-        CategoryPreset::get_default_presets()
+    /// Deletes a category according to `strategy`. With `dry_run` nothing is
+    /// changed and the summary describes what the delete would do; passing that
+    /// summary back as `confirmed` makes the delete fail if it would now differ.
+    pub fn delete_category(
+        &self,
+        id: i64,
+        strategy: DeleteStrategy,
+        dry_run: bool,
+        confirmed: Option<DeleteCategorySummary>,
+    ) -> AppResult<DeleteCategorySummary> {
+        if self.repository.get_category(id)?.is_none() {
+            return Err(AppError::new("NOT_FOUND", "Category not found"));
+        }
+        
+        if let DeleteStrategy::Reassign { target_id } = strategy {
+            if self.repository.get_category(target_id)?.is_none() {
+                return Err(AppError::new("VALIDATION_ERROR", "Target category does not exist"));
+            }
+            if self.repository.is_descendant(target_id, id)? {
+                return Err(AppError::new(
+                    "VALIDATION_ERROR",
+                    "Notes cannot be reassigned to the deleted category or one of its subcategories",
+                ));
+            }
+        }
+        
+        if dry_run {
+            return self.repository.plan_delete(id, &strategy);
+        }
+        
+        self.repository.delete_category(id, &strategy, confirmed.as_ref())
+    }
+
+    pub fn merge_categories(&self, source_id: i64, target_id: i64) -> AppResult<Category> {
+        if source_id == target_id {
+            return Err(AppError::new("VALIDATION_ERROR", "A category cannot be merged into itself"));
+        }
+        
+        if self.repository.get_category(source_id)?.is_none() {
+            return Err(AppError::new("NOT_FOUND", "Source category not found"));
+        }
+        if self.repository.get_category(target_id)?.is_none() {
+            return Err(AppError::new("NOT_FOUND", "Target category not found"));
+        }
+        
+        if self.repository.is_descendant(target_id, source_id)? {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                "A category cannot be merged into one of its own subcategories",
+            ));
+        }
+        
+        self.repository.merge_categories(source_id, target_id)
+    }
+
+    /// Built-in presets followed by the user's own, in their saved order
+    pub fn get_category_presets(&self) -> AppResult<Vec<CategoryPreset>> {
+        let mut presets = CategoryPreset::get_default_presets();
+        presets.extend(self.repository.get_user_presets()?);
+        Ok(presets)
+    }
+
+    pub fn create_preset(&self, mut request: CategoryPresetRequest) -> AppResult<CategoryPreset> {
+        self.validate_preset(None, &mut request)?;
+        self.repository.create_preset(request)
+    }
+
+    pub fn update_preset(&self, id: i64, mut request: CategoryPresetRequest) -> AppResult<CategoryPreset> {
+        self.validate_preset(Some(id), &mut request)?;
+        self.repository.update_preset(id, request)
+    }
+
+    pub fn delete_preset(&self, id: i64) -> AppResult<()> {
+        self.repository.delete_preset(id)
+    }
+
+    pub fn reorder_presets(&self, ordered_ids: Vec<i64>) -> AppResult<()> {
+        self.repository.reorder_presets(&ordered_ids)
+    }
+
+    fn validate_preset(&self, id: Option<i64>, request: &mut CategoryPresetRequest) -> AppResult<()> {
+        if request.name.trim().is_empty() {
+            return Err(AppError::new("VALIDATION_ERROR", "Preset name cannot be empty"));
+        }
+        
+        request.color = normalize_color(&request.color)?;
+        
+        // Preset names are how presets are looked up, so they must stay unique
+        let taken = self.get_category_presets()?.iter()
+            .any(|preset| preset.name == request.name && (preset.is_builtin || preset.id != id));
+        if taken {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                format!("A preset named '{}' already exists", request.name),
+            ));
+        }
+        
+        Ok(())
     }
 
     pub fn create_from_preset(&self, preset_name: &str) -> AppResult<Category> {
-        let presets = self.get_category_presets();
+        let presets = self.get_category_presets()?;
         let preset = presets.iter()
             .find(|p| p.name == preset_name)
             .ok_or_else(|| AppError::new("NOT_FOUND", "Preset not found"))?;
         
-        self.create_category(CreateCategoryRequest {
+        self.create_category(Self::preset_request(preset))
+    }
+
+    /// Creates top-level categories for the named presets (all of them when
+    /// `preset_names` is `None`) in one transaction. Presets whose name is
+    /// already used by a top-level category are skipped.
+    pub fn create_preset_set(&self, preset_names: Option<Vec<String>>) -> AppResult<Vec<Category>> {
+        let presets = self.get_category_presets()?;
+        
+        let selected: Vec<&CategoryPreset> = match preset_names {
+            Some(names) => names.iter()
+                .map(|name| presets.iter()
+                    .find(|p| &p.name == name)
+                    .ok_or_else(|| AppError::new("NOT_FOUND", format!("Preset '{}' not found", name))))
+                .collect::<AppResult<_>>()?,
+            None => presets.iter().collect(),
+        };
+        
+        let existing: Vec<String> = self.repository.get_all_categories()?
+            .into_iter()
+            .filter(|category| category.parent_id.is_none())
+            .map(|category| category.name)
+            .collect();
+        
+        let requests = selected.into_iter()
+            .filter(|preset| !existing.contains(&preset.name))
+            .map(Self::preset_request)
+            .collect::<Vec<_>>();
+        
+        if requests.is_empty() {
+            return Ok(vec![]);
+        }
+        self.repository.create_categories(requests)
+    }
+
+    fn preset_request(preset: &CategoryPreset) -> CreateCategoryRequest {
+        CreateCategoryRequest {
             name: preset.name.clone(),
-            color: preset.color.clone(),
+            color: normalize_color(&preset.color).unwrap_or_else(|_| preset.color.clone()),
             parent_id: None,
-        })
+            icon: Some(preset.icon.clone()).filter(|icon| !icon.is_empty()),
+            description: Some(preset.description.clone()).filter(|description| !description.is_empty()),
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use tauri::State;
+use crate::core::AppState;
+use super::models::ChangeBatch;
+use super::repository::JournalRepository;
+
+#[tauri::command]
+pub fn get_changes_since(
+    state: State<AppState>,
+    cursor: i64,
+    limit: Option<i64>,
+) -> Result<ChangeBatch, String> {
+    let repository = JournalRepository::new(state.db());
+    repository.get_changes_since(cursor, limit)
+        .map_err(|e| e.message)
+}
@@ -0,0 +1,8 @@
+pub mod models;
+pub mod recorder;
+pub mod repository;
+pub mod commands;
+
+pub use recorder::ChangeJournal;
+pub use models::ChangeEntity;
+pub use commands::*;
@@ -0,0 +1,91 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeEntity {
+    Note,
+    Category,
+    Selector,
+    Tag,
+}
+
+impl ChangeEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeEntity::Note => "note",
+            ChangeEntity::Category => "category",
+            ChangeEntity::Selector => "selector",
+            ChangeEntity::Tag => "tag",
+        }
+    }
+    
+    pub fn table(&self) -> &'static str {
+        match self {
+            ChangeEntity::Note => "notes",
+            ChangeEntity::Category => "categories",
+            ChangeEntity::Selector => "selectors",
+            ChangeEntity::Tag => "tags",
+        }
+    }
+    
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "note" => Some(ChangeEntity::Note),
+            "category" => Some(ChangeEntity::Category),
+            "selector" => Some(ChangeEntity::Selector),
+            "tag" => Some(ChangeEntity::Tag),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+impl ChangeOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeOperation::Create => "create",
+            ChangeOperation::Update => "update",
+            ChangeOperation::Delete => "delete",
+        }
+    }
+    
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(ChangeOperation::Create),
+            "update" => Some(ChangeOperation::Update),
+            "delete" => Some(ChangeOperation::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One row of the append-only change journal.
+/// `before` / `after` are full row images, `None` on create / delete respectively.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEntry {
+    pub id: i64,
+    pub entity: ChangeEntity,
+    pub entity_id: i64,
+    pub entity_uuid: Option<String>,
+    pub operation: ChangeOperation,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub device_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeBatch {
+    pub changes: Vec<ChangeEntry>,
+    pub cursor: i64, // Pass back to get_changes_since to continue
+    pub has_more: bool,
+}
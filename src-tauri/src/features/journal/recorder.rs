@@ -0,0 +1,230 @@
+use std::cell::RefCell;
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OptionalExtension};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use serde_json::{Map, Value};
+use chrono::Utc;
+use crate::core::error::{AppError, AppResult};
+use super::models::{ChangeEntity, ChangeOperation};
+
+/// Key of a note image holding the ids of its tags, which aren't a column
+const NOTE_TAGS_FIELD: &str = "tags";
+
+/// Error code from `restore` when the image clashes with another row, e.g. a
+/// category whose name a sibling has taken since
+pub const RESTORE_CONFLICT: &str = "RESTORE_CONFLICT";
+
+thread_local! {
+    /// Journal ids written by this thread while `ChangeJournal::capture` runs
+    static CAPTURED: RefCell<Option<Vec<i64>>> = const { RefCell::new(None) };
+}
+
+/// Writes mutations to the `change_log` table.
+///
+/// Repositories take a snapshot of a row before mutating it and call `record`
+/// afterwards, on the same connection or transaction, so the journal entry is
+/// committed (or rolled back) together with the change itself.
+pub struct ChangeJournal<'a> {
+    conn: &'a Connection,
+    device_id: &'a str,
+}
+
+impl<'a> ChangeJournal<'a> {
+    pub fn new(conn: &'a Connection, device_id: &'a str) -> Self {
+        Self { conn, device_id }
+    }
+    
+    /// Runs `operation` and returns the ids of the journal entries it wrote.
+    /// Entries written meanwhile by other threads, such as folder sync, are
+    /// not included.
+    pub fn capture<T>(operation: impl FnOnce() -> AppResult<T>) -> (Vec<i64>, AppResult<T>) {
+        let outer = CAPTURED.with(|captured| captured.replace(Some(Vec::new())));
+        let result = operation();
+        let ids = CAPTURED.with(|captured| captured.replace(outer)).unwrap_or_default();
+        
+        // A nested capture's entries belong to the enclosing one as well
+        CAPTURED.with(|captured| {
+            if let Some(outer) = captured.borrow_mut().as_mut() {
+                outer.extend(&ids);
+            }
+        });
+        
+        (ids, result)
+    }
+    
+    /// Captures the current row as a JSON object keyed by column name
+    pub fn snapshot(&self, entity: ChangeEntity, id: i64) -> AppResult<Option<Value>> {
+        let mut stmt = self.conn.prepare(
+            &format!("SELECT * FROM {} WHERE id = ?1", entity.table())
+        )?;
+        let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+        
+        let row = stmt.query_row(params![id], |row| {
+            let mut object = Map::new();
+            for (index, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(index)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(i) => Value::from(i),
+                    ValueRef::Real(f) => Value::from(f),
+                    ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).into_owned()),
+                    ValueRef::Blob(_) => continue,
+                };
+                object.insert(column.clone(), value);
+            }
+            Ok(Value::Object(object))
+        }).optional()?;
+        
+        // Tags live in a junction table, so carry them with the note
+        let row = match (entity, row) {
+            (ChangeEntity::Note, Some(Value::Object(mut object))) => {
+                let tag_ids = self.conn.prepare("SELECT tag_id FROM note_tags WHERE note_id = ?1 ORDER BY tag_id")?
+                    .query_map(params![id], |row| row.get::<_, i64>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                object.insert(NOTE_TAGS_FIELD.to_string(), Value::from(tag_ids));
+                Some(Value::Object(object))
+            }
+            (_, row) => row,
+        };
+        
+        Ok(row)
+    }
+    
+    /// Whether the row is still in the state `image` describes (`None` when it
+    /// should not exist). Only the image's columns are compared, so columns
+    /// added since it was journaled don't count as a difference.
+    pub fn matches(&self, entity: ChangeEntity, id: i64, image: Option<&Value>) -> AppResult<bool> {
+        let current = self.snapshot(entity, id)?;
+        
+        Ok(match (current.as_ref(), image) {
+            (None, None) => true,
+            (Some(Value::Object(current)), Some(Value::Object(image))) => {
+                image.iter().all(|(column, value)| current.get(column) == Some(value))
+            }
+            _ => false,
+        })
+    }
+    
+    /// Records the change from `before` to the row's current state.
+    /// The operation is inferred from which side is missing; unchanged rows are skipped.
+    pub fn record(
+        &self,
+        entity: ChangeEntity,
+        id: i64,
+        before: Option<Value>,
+    ) -> AppResult<Option<i64>> {
+        let after = self.snapshot(entity, id)?;
+        
+        let operation = match (&before, &after) {
+            (None, Some(_)) => ChangeOperation::Create,
+            (Some(_), None) => ChangeOperation::Delete,
+            (Some(old), Some(new)) if old != new => ChangeOperation::Update,
+            _ => return Ok(None),
+        };
+        
+        let entity_uuid = after.as_ref()
+            .or(before.as_ref())
+            .and_then(|row| row.get("uuid"))
+            .and_then(|uuid| uuid.as_str())
+            .map(|uuid| uuid.to_string());
+        
+        self.conn.execute(
+            "INSERT INTO change_log (entity, entity_id, entity_uuid, operation, before_json, after_json, device_id, created_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entity.as_str(),
+                id,
+                entity_uuid,
+                operation.as_str(),
+                before.map(|v| v.to_string()),
+                after.map(|v| v.to_string()),
+                self.device_id,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        
+        let change_id = self.conn.last_insert_rowid();
+        CAPTURED.with(|captured| {
+            if let Some(ids) = captured.borrow_mut().as_mut() {
+                ids.push(change_id);
+            }
+        });
+        
+        Ok(Some(change_id))
+    }
+    
+    /// Puts a row back to a journaled image (`None` removes it) and journals that change too.
+    /// Fails with `RESTORE_CONFLICT`, leaving the row as it was, when the image
+    /// breaks a constraint other than its own id.
+    pub fn restore(&self, entity: ChangeEntity, id: i64, image: Option<&Value>) -> AppResult<()> {
+        let before = self.snapshot(entity, id)?;
+        
+        match image {
+            None => {
+                self.conn.execute(
+                    &format!("DELETE FROM {} WHERE id = ?1", entity.table()),
+                    params![id],
+                )?;
+            }
+            Some(Value::Object(row)) => {
+                let tag_ids = row.get(NOTE_TAGS_FIELD).and_then(Value::as_array);
+                let row: Map<String, Value> = row.iter()
+                    .filter(|(column, _)| column.as_str() != NOTE_TAGS_FIELD)
+                    .map(|(column, value)| (column.clone(), value.clone()))
+                    .collect();
+                
+                let columns = row.keys()
+                    .map(|column| format!("\"{}\"", column.replace('"', "")))
+                    .collect::<Vec<_>>();
+                let placeholders = (1..=columns.len())
+                    .map(|i| format!("?{}", i))
+                    .collect::<Vec<_>>();
+                let updates = columns.iter()
+                    .filter(|column| column.as_str() != "\"id\"")
+                    .map(|column| format!("{0} = excluded.{0}", column))
+                    .collect::<Vec<_>>();
+                
+                let values = row.values().map(|value| match value {
+                    Value::Null => SqlValue::Null,
+                    Value::Bool(b) => SqlValue::Integer(*b as i64),
+                    Value::Number(n) => n.as_i64()
+                        .map(SqlValue::Integer)
+                        .unwrap_or_else(|| SqlValue::Real(n.as_f64().unwrap_or_default())),
+                    Value::String(text) => SqlValue::Text(text.clone()),
+                    other => SqlValue::Text(other.to_string()),
+                });
+                
+                self.conn.execute(
+                    &format!(
+                        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+                        entity.table(),
+                        columns.join(", "),
+                        placeholders.join(", "),
+                        updates.join(", ")
+                    ),
+                    params_from_iter(values),
+                ).map_err(|e| match e {
+                    rusqlite::Error::SqliteFailure(ref failure, _) if failure.code == ErrorCode::ConstraintViolation => {
+                        AppError::new(RESTORE_CONFLICT, format!("Cannot restore {} {}: {}", entity.as_str(), id, e))
+                    }
+                    e => e.into(),
+                })?;
+                
+                // Images journaled before tags were tracked leave them alone
+                if let (ChangeEntity::Note, Some(tag_ids)) = (entity, tag_ids) {
+                    self.conn.execute("DELETE FROM note_tags WHERE note_id = ?1", params![id])?;
+                    for tag_id in tag_ids.iter().filter_map(Value::as_i64) {
+                        self.conn.execute(
+                            "INSERT OR IGNORE INTO note_tags (note_id, tag_id) SELECT ?1, id FROM tags WHERE id = ?2",
+                            params![id, tag_id],
+                        )?;
+                    }
+                }
+            }
+            Some(_) => {
+                return Err(AppError::new("JOURNAL_ERROR", format!("Invalid row image for {} {}", entity.as_str(), id)));
+            }
+        }
+        
+        self.record(entity, id, before)?;
+        Ok(())
+    }
+}
\ No newline at end of file
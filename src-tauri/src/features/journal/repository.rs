@@ -0,0 +1,87 @@
+use rusqlite::{params, params_from_iter, Row};
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use crate::infrastructure::database::Database;
+use crate::core::error::AppResult;
+use super::models::*;
+
+const DEFAULT_BATCH_SIZE: i64 = 500;
+
+pub struct JournalRepository {
+    db: Arc<Mutex<Database>>,
+}
+
+impl JournalRepository {
+    pub fn new(db: Arc<Mutex<Database>>) -> Self {
+        Self { db }
+    }
+    
+    pub fn get_changes_since(&self, cursor: i64, limit: Option<i64>) -> AppResult<ChangeBatch> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        let limit = limit.unwrap_or(DEFAULT_BATCH_SIZE).max(1);
+        
+        // Fetch one extra row to know whether another page follows
+        let mut stmt = conn.prepare(
+            "SELECT id, entity, entity_id, entity_uuid, operation, before_json, after_json, device_id, created_at 
+             FROM change_log WHERE id > ?1 ORDER BY id ASC LIMIT ?2"
+        )?;
+        
+        let mut changes = stmt.query_map(params![cursor, limit + 1], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        let has_more = changes.len() as i64 > limit;
+        changes.truncate(limit as usize);
+        
+        let cursor = changes.last().map(|c| c.id).unwrap_or(cursor);
+        
+        Ok(ChangeBatch {
+            changes,
+            cursor,
+            has_more,
+        })
+    }
+    
+    /// The changes with the given ids, oldest first
+    pub fn get_changes(&self, ids: &[i64]) -> AppResult<Vec<ChangeEntry>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let placeholders = (1..=ids.len())
+            .map(|i| format!("?{}", i))
+            .collect::<Vec<_>>();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, entity, entity_id, entity_uuid, operation, before_json, after_json, device_id, created_at 
+             FROM change_log WHERE id IN ({}) ORDER BY id ASC",
+            placeholders.join(", ")
+        ))?;
+        
+        let changes = stmt.query_map(params_from_iter(ids), Self::map_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(changes)
+    }
+    
+    fn map_row(row: &Row) -> rusqlite::Result<ChangeEntry> {
+        let entity: String = row.get(1)?;
+        let operation: String = row.get(4)?;
+        let before: Option<String> = row.get(5)?;
+        let after: Option<String> = row.get(6)?;
+        
+        Ok(ChangeEntry {
+            id: row.get(0)?,
+            entity: ChangeEntity::parse(&entity)
+                .ok_or_else(|| rusqlite::Error::InvalidColumnType(1, "entity".to_string(), rusqlite::types::Type::Text))?,
+            entity_id: row.get(2)?,
+            entity_uuid: row.get(3)?,
+            operation: ChangeOperation::parse(&operation)
+                .ok_or_else(|| rusqlite::Error::InvalidColumnType(4, "operation".to_string(), rusqlite::types::Type::Text))?,
+            before: before.and_then(|json| serde_json::from_str(&json).ok()),
+            after: after.and_then(|json| serde_json::from_str(&json).ok()),
+            device_id: row.get(7)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
+}
@@ -4,4 +4,7 @@ pub mod sync;
 pub mod preferences;
 pub mod export;
 pub mod categories;
-pub mod selectors;
\ No newline at end of file
+pub mod selectors;
+pub mod shortcuts;
+pub mod journal;
+pub mod undo;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use crate::core::error::{AppError, AppResult};
+use crate::features::categories::models::Category;
+use crate::features::selectors::balls;
+use super::models::CreateNoteRequest;
+
+/// A quick capture line such as `Buy milk #errands @Personal !3`, split into
+/// the note text and its `#tag`, `@Category` and `!selector` markers
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickCapture {
+    pub text: String,
+    pub tags: Vec<String>,
+    pub category: Option<String>, // Underscores stand for spaces: @Deep_Work
+    pub selector_id: Option<i64>,
+}
+
+impl QuickCapture {
+    /// Markers are whole words starting with `#`, `@` or `!`; anything else,
+    /// including `!` followed by something other than a ball number, stays
+    /// in the text. A later `@` or `!` replaces an earlier one.
+    pub fn parse(input: &str) -> Self {
+        let mut capture = QuickCapture {
+            text: String::new(),
+            tags: Vec::new(),
+            category: None,
+            selector_id: None,
+        };
+        
+        let lines: Vec<String> = input.lines()
+            .map(|line| {
+                line.split_whitespace()
+                    .filter(|word| !capture.take_marker(word))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+        capture.text = lines.join("\n").trim().to_string();
+        
+        capture
+    }
+    
+    fn take_marker(&mut self, word: &str) -> bool {
+        let mut chars = word.chars();
+        let (sigil, rest) = match chars.next() {
+            Some(sigil) => (sigil, chars.as_str()),
+            None => return false,
+        };
+        if rest.is_empty() {
+            return false;
+        }
+        
+        match sigil {
+            '#' => {
+                if !self.tags.iter().any(|tag| tag == rest) {
+                    self.tags.push(rest.to_string());
+                }
+                true
+            }
+            '@' => {
+                self.category = Some(rest.replace('_', " "));
+                true
+            }
+            '!' => match rest.parse::<i64>() {
+                Ok(id) if balls::is_valid_ball(id) => {
+                    self.selector_id = Some(id);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+    
+    /// Builds an auto-titled note, so its title follows the first line. The
+    /// category must already exist; names are matched ignoring case.
+    pub fn into_request(self, categories: &[Category]) -> AppResult<CreateNoteRequest> {
+        if self.text.is_empty() {
+            return Err(AppError::new("VALIDATION_ERROR", "Quick capture needs some text besides tags"));
+        }
+        
+        let category_id = match self.category {
+            Some(ref name) => {
+                let category = categories.iter()
+                    .find(|category| category.name == *name)
+                    .or_else(|| categories.iter().find(|category| category.name.eq_ignore_ascii_case(name)))
+                    .ok_or_else(|| AppError::new("CATEGORY_NOT_FOUND", format!("No category named '{}'", name)))?;
+                category.id
+            }
+            None => None,
+        };
+        
+        Ok(CreateNoteRequest {
+            title: String::new(),
+            content: self.text,
+            category_id,
+            selector_id: self.selector_id,
+            tags: self.tags,
+            auto_title: Some(true),
+        })
+    }
+}
@@ -1,17 +1,26 @@
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use crate::core::AppState;
+use crate::features::undo::UndoService;
 use super::models::*;
+use crate::features::categories::service::CategoryService;
+use super::capture::QuickCapture;
 use super::service::NoteService;
+use crate::features::sync::watcher::sync_after_change;
+use crate::features::windows::tray::refresh_tray;
 
 #[tauri::command]
 pub fn create_note(
+    app: AppHandle,
     state: State<AppState>,
     request: CreateNoteRequest,
 ) -> Result<Note, String> {
     println!("[DEBUG] create_note command - selector_id: {:?}", request.selector_id);
     let service = NoteService::new(state.db());
-    service.create_note(request)
-        .map_err(|e| e.message)
+    let note = service.create_note(request)
+        .map_err(|e| e.message)?;
+    refresh_tray(&app);
+    sync_after_change(&app);
+    Ok(note)
 }
 
 #[tauri::command]
@@ -24,6 +33,16 @@ pub fn get_note(
         .map_err(|e| e.message)
 }
 
+#[tauri::command]
+pub fn get_note_by_uuid(
+    state: State<AppState>,
+    uuid: String,
+) -> Result<Option<Note>, String> {
+    let service = NoteService::new(state.db());
+    service.get_note_by_uuid(&uuid)
+        .map_err(|e| e.message)
+}
+
 #[tauri::command]
 pub fn search_notes(
     state: State<AppState>,
@@ -34,6 +53,16 @@ pub fn search_notes(
         .map_err(|e| e.message)
 }
 
+#[tauri::command]
+pub fn get_notes_grouped_by_selector(
+    state: State<AppState>,
+    options: SearchOptions,
+) -> Result<Vec<SelectorGroup>, String> {
+    let service = NoteService::new(state.db());
+    service.get_notes_grouped_by_selector(options)
+        .map_err(|e| e.message)
+}
+
 #[tauri::command]
 pub fn get_all_notes(
     state: State<AppState>,
@@ -45,6 +74,7 @@ pub fn get_all_notes(
 
 #[tauri::command]
 pub fn update_note(
+    app: AppHandle,
     state: State<AppState>,
     request: UpdateNoteRequest,
 ) -> Result<Note, String> {
@@ -52,20 +82,44 @@ pub fn update_note(
     println!("🔧 Backend selector_id reçu: {:?}", request.selector_id);
     
     let service = NoteService::new(state.db());
-    service.update_note(request)
-        .map_err(|e| e.message)
+    
+    // Only renames and moves are undoable; content is saved as the user
+    // types and would push everything else out of the history
+    let current = service.get_note(request.id)
+        .map_err(|e| e.message)?;
+    let label = match current {
+        Some(ref note) if note.category_id != request.category_id => Some("Move note"),
+        Some(ref note) if note.title != request.title => Some("Rename note"),
+        _ => None,
+    };
+    
+    let note = match label {
+        Some(label) => {
+            let undo = UndoService::new(state.db(), state.undo_history());
+            undo.record(label, || service.update_note(request))
+        }
+        None => service.update_note(request),
+    }
+    .map_err(|e| e.message)?;
+    refresh_tray(&app);
+    sync_after_change(&app);
+    Ok(note)
 }
 
 #[tauri::command]
 pub fn delete_note(
+    app: AppHandle,
     state: State<AppState>,
     id: i64,
 ) -> Result<(), String> {
     println!("delete_note command called with id: {}", id);
     let service = NoteService::new(state.db());
-    match service.delete_note(id) {
+    let undo = UndoService::new(state.db(), state.undo_history());
+    match undo.record("Delete note", || service.delete_note(id)) {
         Ok(_) => {
             println!("Note {} deleted successfully", id);
+            refresh_tray(&app);
+            sync_after_change(&app);
             Ok(())
         },
         Err(e) => {
@@ -73,4 +127,38 @@ pub fn delete_note(
             Err(e.message)
         }
     }
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn reorder_notes(
+    state: State<AppState>,
+    category_id: Option<i64>,
+    ordered_ids: Vec<i64>,
+) -> Result<(), String> {
+    let service = NoteService::new(state.db());
+    let undo = UndoService::new(state.db(), state.undo_history());
+    undo.record("Reorder notes", || service.reorder_notes(category_id, ordered_ids))
+        .map_err(|e| e.message)
+}
+
+/// Creates a note from one line of text with `#tag`, `@Category` and
+/// `!selector` markers, as typed in the quick capture window
+#[tauri::command]
+pub fn quick_capture(
+    app: AppHandle,
+    state: State<AppState>,
+    text: String,
+) -> Result<Note, String> {
+    let categories = CategoryService::new(state.db()).get_all_categories()
+        .map_err(|e| e.message)?;
+    let request = QuickCapture::parse(&text).into_request(&categories)
+        .map_err(|e| e.message)?;
+    
+    let note = NoteService::new(state.db()).create_note(request)
+        .map_err(|e| e.message)?;
+    
+    refresh_tray(&app);
+    sync_after_change(&app);
+    let _ = app.emit_to("main", "note-captured", &note);
+    Ok(note)
+}
@@ -1,6 +1,8 @@
+pub mod capture;
 pub mod commands;
 pub mod service;
 pub mod models;
 pub mod repository;
+pub mod title;
 
 pub use commands::*;
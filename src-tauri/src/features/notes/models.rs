@@ -4,16 +4,20 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Note {
     pub id: Option<i64>,
+    pub uuid: String,
     pub title: String,
     pub content: String,
     pub category_id: Option<i64>,
     pub selector_id: Option<i64>,
     pub is_pinned: bool,
+    pub sort_order: Option<i64>, // Manual position within the category, if any
+    pub auto_title: bool, // Title follows the first line of content until set by hand
     pub tags: Vec<Tag>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Category {
     pub id: Option<i64>,
@@ -25,17 +29,21 @@ pub struct Category {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Tag {
     pub id: Option<i64>,
+    pub uuid: String,
     pub name: String,
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateNoteRequest {
-    pub title: String,
+    #[serde(default)]
+    pub title: String, // Left empty to derive it from the content
     pub content: String,
     pub category_id: Option<i64>,
     pub selector_id: Option<i64>,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub auto_title: Option<bool>, // Derive even when a title is given
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,15 +53,40 @@ pub struct UpdateNoteRequest {
     pub content: String,
     pub category_id: Option<i64>,
     pub selector_id: Option<i64>,
-    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>, // None keeps the note's tags
     pub is_pinned: bool,
+    #[serde(default)]
+    pub auto_title: Option<bool>, // None keeps the note's mode, unless the title was edited
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchOptions {
     pub query: String,
     pub category_id: Option<i64>,
+    #[serde(default)]
+    pub include_subcategories: bool, // Also match notes filed under descendants of category_id
     pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub selector_ids: Option<Vec<i64>>, // Notes in any of these selectors
+    #[serde(default)]
+    pub without_selector: bool, // Also (or only) notes with no selector
     pub limit: Option<i32>,
     pub offset: Option<i32>,
-}
\ No newline at end of file
+}
+
+/// Notes sharing a selector, for the board view
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectorGroup {
+    pub selector_id: Option<i64>, // None for notes without a selector
+    pub count: usize,
+    pub notes: Vec<Note>,
+}
+
+/// Counts shown in the tray tooltip
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct NoteStats {
+    pub total: i64,
+    pub pinned: i64,
+    pub created_today: i64,
+}
@@ -1,8 +1,11 @@
-use rusqlite::{params, params_from_iter, OptionalExtension, Result as SqlResult};
+use rusqlite::{params, params_from_iter, OptionalExtension, Result as SqlResult, Row};
 use chrono::{DateTime, Utc};
+use uuid::Uuid;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use crate::infrastructure::database::Database;
 use crate::core::error::{AppError, AppResult};
+use crate::features::journal::{ChangeJournal, ChangeEntity};
 use super::models::*;
 
 pub struct NoteRepository {
@@ -18,40 +21,46 @@ impl NoteRepository {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         let now = Utc::now();
-        
+        let uuid = Uuid::new_v4().to_string();
         
         let tx = conn.unchecked_transaction()
             .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
         
         tx.execute(
-            "INSERT INTO notes (title, content, category_id, selector_id, is_pinned, created_at, updated_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO notes (uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at, auto_title) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
+                uuid,
                 request.title,
                 request.content,
                 request.category_id,
                 request.selector_id,
                 0, // is_pinned default to false
                 now.to_rfc3339(),
-                now.to_rfc3339()
+                now.to_rfc3339(),
+                request.auto_title.unwrap_or(false)
             ],
         )?;
         
         let note_id = tx.last_insert_rowid();
-        
-        // Skip tags for now
+        self.set_note_tags(&tx, db.device_id(), note_id, &request.tags)?;
+        ChangeJournal::new(&tx, db.device_id()).record(ChangeEntity::Note, note_id, None)?;
         
         tx.commit()?;
+        let tags = Self::load_tags(conn, note_id)?;
         
         // Return a simple note object without fetching from DB
         Ok(Note {
             id: Some(note_id),
+            uuid,
             title: request.title.clone(),
             content: request.content.clone(),
             category_id: request.category_id,
             selector_id: request.selector_id,
             is_pinned: false,
-            tags: vec![],
+            sort_order: None,
+            auto_title: request.auto_title.unwrap_or(false),
+            tags,
             created_at: now,
             updated_at: now,
         })
@@ -62,45 +71,90 @@ impl NoteRepository {
         let conn = db.connection();
         
         let mut stmt = conn.prepare(
-            "SELECT id, title, content, category_id, selector_id, is_pinned, created_at, updated_at 
+            "SELECT id, uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at, sort_order, auto_title 
              FROM notes WHERE id = ?1"
         )?;
         
-        let note = stmt.query_row(params![id], |row| {
-            let selector_id: Option<i64> = row.get(4)?;
-            Ok(Note {
-                id: Some(row.get(0)?),
-                title: row.get(1)?,
-                content: row.get(2)?,
-                category_id: row.get(3)?,
-                selector_id,
-                is_pinned: row.get::<_, i32>(5)? != 0,
-                tags: vec![],
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            })
-        }).optional()?;
-        
-        if let Some(note) = note {
-            // Temporairement, on skip les tags
-            // note.tags = self.get_note_tags(note.id.unwrap())?;
+        let note = stmt.query_row(params![id], Self::map_row).optional()?;
+        
+        if let Some(mut note) = note {
+            note.tags = Self::load_tags(conn, id)?;
             Ok(Some(note))
         } else {
             Ok(None)
         }
     }
     
+    pub fn get_note_by_uuid(&self, uuid: &str) -> AppResult<Option<Note>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at, sort_order, auto_title 
+             FROM notes WHERE uuid = ?1"
+        )?;
+        
+        let mut note = stmt.query_row(params![uuid], Self::map_row).optional()?;
+        if let Some(ref mut note) = note {
+            note.tags = Self::load_tags(conn, note.id.unwrap_or_default())?;
+        }
+        Ok(note)
+    }
+    
+    /// Most recently updated notes first, regardless of pinning
+    pub fn get_recent_notes(&self, limit: i64) -> AppResult<Vec<Note>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at, sort_order, auto_title 
+             FROM notes ORDER BY updated_at DESC LIMIT ?1"
+        )?;
+        
+        let notes = stmt.query_map(params![limit], Self::map_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        Ok(notes)
+    }
+    
+    pub fn get_pinned_notes(&self) -> AppResult<Vec<Note>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at, sort_order, auto_title 
+             FROM notes WHERE is_pinned = 1 ORDER BY title COLLATE NOCASE ASC"
+        )?;
+        
+        let notes = stmt.query_map([], Self::map_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        Ok(notes)
+    }
+    
+    /// `today_start` is the RFC 3339 UTC instant of local midnight
+    pub fn get_note_stats(&self, today_start: &str) -> AppResult<NoteStats> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let stats = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(is_pinned = 1), 0), COALESCE(SUM(created_at >= ?1), 0) FROM notes",
+            params![today_start],
+            |row| Ok(NoteStats {
+                total: row.get(0)?,
+                pinned: row.get(1)?,
+                created_today: row.get(2)?,
+            }),
+        )?;
+        
+        Ok(stats)
+    }
+    
     pub fn search_notes(&self, options: &SearchOptions) -> AppResult<Vec<Note>> {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         
         let mut query = String::from(
-            "SELECT DISTINCT n.id, n.title, n.content, n.category_id, n.selector_id, n.is_pinned, 
-                    n.created_at, n.updated_at
+            "SELECT DISTINCT n.id, n.uuid, n.title, n.content, n.category_id, n.selector_id, n.is_pinned, 
+                    n.created_at, n.updated_at, n.sort_order, n.auto_title
              FROM notes n"
         );
         
@@ -116,10 +170,40 @@ impl NoteRepository {
         
         // Category filter
         if let Some(cat_id) = options.category_id {
-            where_clauses.push("n.category_id = ?".to_string());
+            if options.include_subcategories {
+                where_clauses.push(
+                    "n.category_id IN (
+                        WITH RECURSIVE subtree(id) AS (
+                            SELECT ?
+                            UNION
+                            SELECT c.id FROM categories c JOIN subtree ON c.parent_id = subtree.id
+                        )
+                        SELECT id FROM subtree
+                    )".to_string()
+                );
+            } else {
+                where_clauses.push("n.category_id = ?".to_string());
+            }
             params.push(Box::new(cat_id));
         }
         
+        // Selector filter: any of the listed selectors, and/or no selector at all
+        let selector_ids = options.selector_ids.as_deref().unwrap_or_default();
+        if !selector_ids.is_empty() || options.without_selector {
+            let mut alternatives = Vec::new();
+            if !selector_ids.is_empty() {
+                let placeholders = selector_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+                alternatives.push(format!("n.selector_id IN ({})", placeholders));
+                for selector_id in selector_ids {
+                    params.push(Box::new(*selector_id));
+                }
+            }
+            if options.without_selector {
+                alternatives.push("n.selector_id IS NULL".to_string());
+            }
+            where_clauses.push(format!("({})", alternatives.join(" OR ")));
+        }
+        
         // Tags filter
         if let Some(ref tags) = options.tags {
             if !tags.is_empty() {
@@ -138,7 +222,12 @@ impl NoteRepository {
             query.push_str(&where_clauses.join(" AND "));
         }
         
-        query.push_str(" ORDER BY n.is_pinned DESC, n.updated_at DESC");
+        // Manual order is per category, so it only applies within one
+        if options.category_id.is_some() && !options.include_subcategories {
+            query.push_str(" ORDER BY n.is_pinned DESC, n.sort_order IS NULL, n.sort_order ASC, n.updated_at DESC");
+        } else {
+            query.push_str(" ORDER BY n.is_pinned DESC, n.updated_at DESC");
+        }
         
         if let Some(limit) = options.limit {
             query.push_str(&format!(" LIMIT {}", limit));
@@ -149,27 +238,13 @@ impl NoteRepository {
         
         let mut stmt = conn.prepare(&query)?;
         
-        let notes = stmt.query_map(params_from_iter(params.iter()), |row| {
-            let selector_id: Option<i64> = row.get(4)?;
-            Ok(Note {
-                id: Some(row.get(0)?),
-                title: row.get(1)?,
-                content: row.get(2)?,
-                category_id: row.get(3)?,
-                selector_id,
-                is_pinned: row.get::<_, i32>(5)? != 0,
-                tags: vec![],
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(6)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            })
-        })?
-        .collect::<SqlResult<Vec<_>>>()?;
-        
-        // Pour l'instant, sautons la récupération des tags
+        let mut notes = stmt.query_map(params_from_iter(params.iter()), Self::map_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        let mut tags = Self::load_all_tags(conn)?;
+        for note in &mut notes {
+            note.tags = note.id.and_then(|id| tags.remove(&id)).unwrap_or_default();
+        }
         Ok(notes)
     }
     
@@ -178,10 +253,25 @@ impl NoteRepository {
         let conn = db.connection();
         let now = Utc::now();
         
+        let uuid: String = conn.query_row(
+            "SELECT uuid FROM notes WHERE id = ?1",
+            params![request.id],
+            |row| row.get(0)
+        ).optional()?
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("Note with id {} not found", request.id)))?;
         
-        conn.execute(
-            "UPDATE notes SET title = ?1, content = ?2, category_id = ?3, selector_id = ?4, is_pinned = ?5, updated_at = ?6 
-             WHERE id = ?7",
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        let before = journal.snapshot(ChangeEntity::Note, request.id)?;
+        
+        // Moving to another category drops the manual position from the old one
+        let (sort_order, auto_title): (Option<i64>, bool) = tx.query_row(
+            "UPDATE notes SET title = ?1, content = ?2, category_id = ?3, selector_id = ?4, is_pinned = ?5, updated_at = ?6,
+                    sort_order = CASE WHEN category_id IS ?3 THEN sort_order ELSE NULL END,
+                    auto_title = COALESCE(?8, auto_title)
+             WHERE id = ?7
+             RETURNING sort_order, auto_title",
             params![
                 request.title,
                 request.content,
@@ -189,19 +279,31 @@ impl NoteRepository {
                 request.selector_id,
                 if request.is_pinned { 1 } else { 0 },
                 now.to_rfc3339(),
-                request.id
+                request.id,
+                request.auto_title
             ],
+            |row| Ok((row.get(0)?, row.get(1)?))
         )?;
         
+        if let Some(ref tags) = request.tags {
+            self.set_note_tags(&tx, db.device_id(), request.id, tags)?;
+        }
+        journal.record(ChangeEntity::Note, request.id, before)?;
+        tx.commit()?;
+        let tags = Self::load_tags(conn, request.id)?;
+        
         // Pour l'instant, on retourne juste la note mise à jour
         Ok(Note {
             id: Some(request.id),
+            uuid,
             title: request.title.clone(),
             content: request.content.clone(),
             category_id: request.category_id,
             selector_id: request.selector_id,
             is_pinned: request.is_pinned,
-            tags: vec![], // On skip les tags pour l'instant
+            sort_order,
+            auto_title,
+            tags,
             created_at: now, // Pas idéal mais ok pour l'instant
             updated_at: now,
         })
@@ -214,6 +316,8 @@ impl NoteRepository {
         // Commencer une transaction
         let tx = conn.unchecked_transaction()
             .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        let before = journal.snapshot(ChangeEntity::Note, id)?;
         
         // Supprimer d'abord les tags associés
         tx.execute(
@@ -226,14 +330,70 @@ impl NoteRepository {
             "DELETE FROM notes WHERE id = ?1",
             params![id],
         )?;
+        journal.record(ChangeEntity::Note, id, before)?;
         
         tx.commit()?;
         
         Ok(())
     }
     
-    #[allow(dead_code)]
-    fn get_or_create_tag(&self, tx: &rusqlite::Transaction, name: &str) -> AppResult<i64> {
+    /// Stores the manual order of the notes in a category. Notes of the
+    /// category left out of `ordered_ids` go back to the default order.
+    pub fn reorder_notes(&self, category_id: Option<i64>, ordered_ids: &[i64]) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let note_ids = tx.prepare("SELECT id FROM notes WHERE category_id IS ?1")?
+            .query_map(params![category_id], |row| row.get::<_, i64>(0))?
+            .collect::<SqlResult<Vec<_>>>()?;
+        
+        if let Some(stray) = ordered_ids.iter().find(|id| !note_ids.contains(id)) {
+            return Err(AppError::new(
+                "VALIDATION_ERROR",
+                format!("Note {} does not belong to this category", stray),
+            ));
+        }
+        
+        for note_id in note_ids {
+            let position = ordered_ids.iter().position(|id| *id == note_id).map(|index| index as i64);
+            let before = journal.snapshot(ChangeEntity::Note, note_id)?;
+            tx.execute(
+                "UPDATE notes SET sort_order = ?1 WHERE id = ?2 AND sort_order IS NOT ?1",
+                params![position, note_id],
+            )?;
+            journal.record(ChangeEntity::Note, note_id, before)?;
+        }
+        
+        tx.commit()?;
+        Ok(())
+    }
+    
+    /// Replaces the note's tags. Names are trimmed, a leading `#` is
+    /// dropped, and tags no other note uses are kept for autocompletion.
+    fn set_note_tags(&self, tx: &rusqlite::Transaction, device_id: &str, note_id: i64, tags: &[String]) -> AppResult<()> {
+        tx.execute("DELETE FROM note_tags WHERE note_id = ?1", params![note_id])?;
+        
+        for name in tags {
+            let name = name.trim().trim_start_matches('#').trim();
+            if name.is_empty() {
+                continue;
+            }
+            
+            let tag_id = self.get_or_create_tag(tx, device_id, name)?;
+            tx.execute(
+                "INSERT OR IGNORE INTO note_tags (note_id, tag_id) VALUES (?1, ?2)",
+                params![note_id, tag_id],
+            )?;
+        }
+        
+        Ok(())
+    }
+    
+    fn get_or_create_tag(&self, tx: &rusqlite::Transaction, device_id: &str, name: &str) -> AppResult<i64> {
         let existing: Option<i64> = tx.query_row(
             "SELECT id FROM tags WHERE name = ?1",
             params![name],
@@ -244,37 +404,84 @@ impl NoteRepository {
             Some(id) => Ok(id),
             None => {
                 tx.execute(
-                    "INSERT INTO tags (name) VALUES (?1)",
-                    params![name],
+                    "INSERT INTO tags (uuid, name, created_at) VALUES (?1, ?2, ?3)",
+                    params![Uuid::new_v4().to_string(), name, Utc::now().to_rfc3339()],
                 )?;
-                Ok(tx.last_insert_rowid())
+                let tag_id = tx.last_insert_rowid();
+                ChangeJournal::new(tx, device_id).record(ChangeEntity::Tag, tag_id, None)?;
+                Ok(tag_id)
             }
         }
     }
     
-    #[allow(dead_code)]
-    fn get_note_tags(&self, note_id: i64) -> AppResult<Vec<Tag>> {
-        let db = self.db.lock().unwrap();
-        let conn = db.connection();
+    fn load_tags(conn: &rusqlite::Connection, note_id: i64) -> AppResult<Vec<Tag>> {
+        let mut stmt = conn.prepare(
+            "SELECT t.id, t.uuid, t.name, t.created_at 
+             FROM tags t 
+             JOIN note_tags nt ON t.id = nt.tag_id 
+             WHERE nt.note_id = ?1
+             ORDER BY t.name COLLATE NOCASE"
+        )?;
+        
+        let tags = stmt.query_map(params![note_id], Self::map_tag_row)?
+            .collect::<SqlResult<Vec<_>>>()?;
         
+        Ok(tags)
+    }
+    
+    /// Tags of every note in one query, for list results
+    fn load_all_tags(conn: &rusqlite::Connection) -> AppResult<HashMap<i64, Vec<Tag>>> {
         let mut stmt = conn.prepare(
-            "SELECT t.id, t.name, t.created_at 
+            "SELECT t.id, t.uuid, t.name, t.created_at, nt.note_id 
              FROM tags t 
              JOIN note_tags nt ON t.id = nt.tag_id 
-             WHERE nt.note_id = ?1"
+             ORDER BY t.name COLLATE NOCASE"
         )?;
         
-        let tags = stmt.query_map(params![note_id], |row| {
-            Ok(Tag {
-                id: Some(row.get(0)?),
-                name: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            })
-        })?
-        .collect::<SqlResult<Vec<_>>>()?;
+        let mut tags: HashMap<i64, Vec<Tag>> = HashMap::new();
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(4)?, Self::map_tag_row(row)?)))?;
+        for row in rows {
+            let (note_id, tag) = row?;
+            tags.entry(note_id).or_default().push(tag);
+        }
         
         Ok(tags)
     }
+    
+    fn map_tag_row(row: &Row) -> SqlResult<Tag> {
+        // Rows written by the schema default use SQLite's "YYYY-MM-DD HH:MM:SS"
+        let created_at: String = row.get(3)?;
+        let created_at = DateTime::parse_from_rfc3339(&created_at)
+            .map(|date| date.with_timezone(&Utc))
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S").map(|date| date.and_utc()))
+            .unwrap_or_else(|_| Utc::now());
+        
+        Ok(Tag {
+            id: Some(row.get(0)?),
+            uuid: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+            name: row.get(2)?,
+            created_at,
+        })
+    }
+    
+    fn map_row(row: &Row) -> SqlResult<Note> {
+        Ok(Note {
+            id: Some(row.get(0)?),
+            uuid: row.get(1)?,
+            title: row.get(2)?,
+            content: row.get(3)?,
+            category_id: row.get(4)?,
+            selector_id: row.get(5)?,
+            is_pinned: row.get::<_, i32>(6)? != 0,
+            sort_order: row.get(9)?,
+            auto_title: row.get(10)?,
+            tags: vec![],
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
 }
\ No newline at end of file
@@ -1,8 +1,10 @@
 use std::sync::{Arc, Mutex};
+use chrono::{Local, Utc};
 use crate::infrastructure::database::Database;
-use crate::core::error::AppResult;
+use crate::core::error::{AppError, AppResult};
 use super::models::*;
 use super::repository::NoteRepository;
+use super::title::derive_title;
 
 pub struct NoteService {
     repository: NoteRepository,
@@ -15,7 +17,14 @@ impl NoteService {
         }
     }
     
-    pub fn create_note(&self, request: CreateNoteRequest) -> AppResult<Note> {
+    /// A blank title, or `auto_title`, makes the note take its title from
+    /// the first line of content
+    pub fn create_note(&self, mut request: CreateNoteRequest) -> AppResult<Note> {
+        if request.auto_title == Some(true) || request.title.trim().is_empty() {
+            request.title = derive_title(&request.content);
+            request.auto_title = Some(true);
+        }
+        
         self.repository.create_note(&request)
     }
     
@@ -23,26 +32,101 @@ impl NoteService {
         self.repository.get_note(id)
     }
     
+    pub fn get_note_by_uuid(&self, uuid: &str) -> AppResult<Option<Note>> {
+        self.repository.get_note_by_uuid(uuid)
+    }
+    
     pub fn search_notes(&self, options: SearchOptions) -> AppResult<Vec<Note>> {
         self.repository.search_notes(&options)
     }
     
+    pub fn get_recent_notes(&self, limit: i64) -> AppResult<Vec<Note>> {
+        self.repository.get_recent_notes(limit)
+    }
+    
+    pub fn get_pinned_notes(&self) -> AppResult<Vec<Note>> {
+        self.repository.get_pinned_notes()
+    }
+    
+    /// Totals for the tray, with "today" meaning since local midnight
+    pub fn get_note_stats(&self) -> AppResult<NoteStats> {
+        let today_start = Local::now().date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .map(|midnight| midnight.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+        
+        self.repository.get_note_stats(&today_start.to_rfc3339())
+    }
+    
     pub fn get_all_notes(&self) -> AppResult<Vec<Note>> {
         let options = SearchOptions {
             query: String::new(),
             category_id: None,
+            include_subcategories: false,
             tags: None,
+            selector_ids: None,
+            without_selector: false,
             limit: None,
             offset: None,
         };
         self.repository.search_notes(&options)
     }
     
-    pub fn update_note(&self, request: UpdateNoteRequest) -> AppResult<Note> {
+    /// Runs the search and buckets the results per selector, ball order first
+    /// and notes without a selector last
+    pub fn get_notes_grouped_by_selector(&self, options: SearchOptions) -> AppResult<Vec<SelectorGroup>> {
+        let mut groups: Vec<SelectorGroup> = Vec::new();
+        
+        for note in self.repository.search_notes(&options)? {
+            match groups.iter_mut().find(|group| group.selector_id == note.selector_id) {
+                Some(group) => group.notes.push(note),
+                None => groups.push(SelectorGroup {
+                    selector_id: note.selector_id,
+                    count: 0,
+                    notes: vec![note],
+                }),
+            }
+        }
+        
+        for group in &mut groups {
+            group.count = group.notes.len();
+        }
+        groups.sort_by_key(|group| (group.selector_id.is_none(), group.selector_id));
+        
+        Ok(groups)
+    }
+    
+    /// Auto-titled notes are retitled from their content. Sending a title
+    /// different from the stored one counts as setting it by hand and ends
+    /// auto mode; clearing the title or passing `auto_title` resumes it.
+    pub fn update_note(&self, mut request: UpdateNoteRequest) -> AppResult<Note> {
+        let current = self.repository.get_note(request.id)?
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("Note with id {} not found", request.id)))?;
+        
+        let auto_title = if request.title.trim().is_empty() {
+            true
+        } else {
+            request.auto_title.unwrap_or(current.auto_title && request.title == current.title)
+        };
+        
+        if auto_title {
+            request.title = derive_title(&request.content);
+        }
+        request.auto_title = Some(auto_title);
+        
         self.repository.update_note(&request)
     }
     
     pub fn delete_note(&self, id: i64) -> AppResult<()> {
         self.repository.delete_note(id)
     }
+    
+    pub fn reorder_notes(&self, category_id: Option<i64>, ordered_ids: Vec<i64>) -> AppResult<()> {
+        let mut seen = std::collections::HashSet::new();
+        if !ordered_ids.iter().all(|id| seen.insert(*id)) {
+            return Err(AppError::new("VALIDATION_ERROR", "Note order contains duplicate ids"));
+        }
+        self.repository.reorder_notes(category_id, &ordered_ids)
+    }
 }
\ No newline at end of file
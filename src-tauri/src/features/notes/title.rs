@@ -0,0 +1,122 @@
+/// Longest derived title, in characters, before it's cut at a word boundary
+const MAX_TITLE_CHARS: usize = 80;
+
+pub const UNTITLED: &str = "Untitled";
+
+/// Title from the first line of content with text in it, as plain text:
+/// heading, quote and list markers, emphasis and link syntax are dropped.
+/// Lines that are only markup, like `---` or a code fence, are skipped.
+pub fn derive_title(content: &str) -> String {
+    content.lines()
+        .map(plain_text)
+        .find(|line| !line.is_empty())
+        .map(|line| truncate(&line))
+        .unwrap_or_else(|| UNTITLED.to_string())
+}
+
+fn plain_text(line: &str) -> String {
+    let mut line = line.trim();
+    if line.starts_with("```") || line.starts_with("~~~") {
+        return String::new();
+    }
+    
+    // Block markers at the start of the line, possibly nested ("> - [ ] ")
+    loop {
+        let stripped = line.trim_start_matches('#').trim_start_matches('>');
+        let stripped = ["- [ ] ", "- [x] ", "- [X] ", "- ", "* ", "+ "].iter()
+            .find_map(|marker| stripped.trim_start().strip_prefix(marker))
+            .unwrap_or(stripped)
+            .trim_start();
+        let stripped = strip_ordered_marker(stripped);
+        
+        if stripped.len() == line.len() {
+            break;
+        }
+        line = stripped;
+    }
+    
+    let text = strip_links(line);
+    let text: String = text.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '~'))
+        .collect();
+    
+    // Rules like "---" or "===" and fences leave nothing worth a title
+    let text = text.trim();
+    if text.chars().all(|c| matches!(c, '-' | '=' | '|' | ':' | ' ')) {
+        String::new()
+    } else {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// "1. " or "12) "
+fn strip_ordered_marker(line: &str) -> &str {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return line;
+    }
+    
+    let rest = &line[digits..];
+    rest.strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))
+        .map(str::trim_start)
+        .unwrap_or(line)
+}
+
+/// `[text](url)`, `![alt](url)` and `[[Wiki Link]]` become their text
+fn strip_links(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    
+    while let Some(start) = rest.find('[') {
+        let (before, from_bracket) = rest.split_at(start);
+        out.push_str(before.strip_suffix('!').unwrap_or(before));
+        
+        if let Some(inner) = from_bracket.strip_prefix("[[") {
+            if let Some(end) = inner.find("]]") {
+                // [[Target|Label]] shows the label
+                let link = &inner[..end];
+                out.push_str(link.rsplit('|').next().unwrap_or(link));
+                rest = &inner[end + 2..];
+                continue;
+            }
+        }
+        
+        let inner = &from_bracket[1..];
+        match inner.find(']') {
+            Some(end) if inner[end + 1..].starts_with('(') => {
+                out.push_str(&inner[..end]);
+                let after = &inner[end + 2..];
+                rest = match after.find(')') {
+                    Some(close) => &after[close + 1..],
+                    None => "",
+                };
+            }
+            _ => {
+                if before.ends_with('!') {
+                    out.push('!');
+                }
+                out.push('[');
+                rest = inner;
+            }
+        }
+    }
+    
+    out.push_str(rest);
+    out
+}
+
+fn truncate(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return title.to_string();
+    }
+    
+    let cut: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    // Prefer ending on a whole word unless that throws away most of the line
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > MAX_TITLE_CHARS / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    
+    format!("{}…", cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation()))
+}
@@ -1,6 +1,9 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 use super::models::Preferences;
-use super::PreferencesManager;
+use super::{PreferencesManager, PreferencesWarning};
+use crate::features::shortcuts::ShortcutService;
+use crate::features::windows::service::WindowService;
+use crate::features::windows::tray::refresh_tray;
 
 #[tauri::command]
 pub fn get_preferences(app_handle: AppHandle) -> Result<Preferences, String> {
@@ -8,12 +11,39 @@ pub fn get_preferences(app_handle: AppHandle) -> Result<Preferences, String> {
     Ok(manager.load())
 }
 
+/// Why the preferences file was repaired at launch, once; `None` afterwards
+#[tauri::command]
+pub fn take_preferences_warning(warning: State<'_, PreferencesWarning>) -> Option<String> {
+    warning.take()
+}
+
 #[tauri::command]
 pub fn update_preferences(
     app_handle: AppHandle,
-    preferences: Preferences,
+    mut preferences: Preferences,
 ) -> Result<(), String> {
     let manager = PreferencesManager::new(&app_handle);
+    
+    // Fields owned by the backend are not editable from the UI
+    let current = manager.load();
+    preferences.sync.device_id = current.sync.device_id;
+    preferences.sync.last_sync = current.sync.last_sync;
+    preferences.sync.last_error = current.sync.last_error;
+    
+    // Rebind before saving so a rejected shortcut leaves the old ones in place
+    if preferences.shortcuts != current.shortcuts {
+        ShortcutService::apply(&app_handle, &preferences.shortcuts)
+            .map_err(|e| e.message)?;
+    }
+    
     manager.save(&preferences)
-        .map_err(|e| format!("Failed to save preferences: {:?}", e))
+        .map_err(|e| format!("Failed to save preferences: {:?}", e))?;
+    
+    // Floating notes without their own opacity follow the new transparency
+    if let Err(e) = WindowService::refresh_opacity(&app_handle) {
+        eprintln!("Failed to apply window transparency: {}", e.message);
+    }
+    refresh_tray(&app_handle);
+    
+    Ok(())
 }
\ No newline at end of file
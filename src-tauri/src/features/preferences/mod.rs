@@ -2,5 +2,5 @@ pub mod models;
 pub mod service;
 pub mod commands;
 
-pub use service::PreferencesManager;
+pub use service::{PreferencesManager, PreferencesWarning};
 pub use commands::*;
\ No newline at end of file
@@ -1,16 +1,39 @@
 use serde::{Serialize, Deserialize};
+use crate::features::shortcuts::ShortcutPreferences;
 use crate::features::sync::SyncSettings;
 
+/// Schema version written by this build; older files are migrated on load
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Window opacity range from the PRD, 50–100%
+pub const MIN_OPACITY: f64 = 0.5;
+pub const MAX_OPACITY: f64 = 1.0;
+
+pub fn clamp_opacity(value: f64) -> f64 {
+    if value.is_nan() {
+        MAX_OPACITY
+    } else {
+        value.clamp(MIN_OPACITY, MAX_OPACITY)
+    }
+}
+
+/// Every section and field falls back to its default on its own, so a file
+/// from an older build keeps whatever it already had.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Preferences {
+    pub version: u32,
     pub sync: SyncSettings,
     pub window: WindowPreferences,
     pub editor: EditorPreferences,
     pub export: ExportPreferences,
     pub appearance: AppearancePreferences,
+    pub tray: TrayPreferences,
+    pub shortcuts: ShortcutPreferences,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WindowPreferences {
     pub default_width: f64,
     pub default_height: f64,
@@ -18,6 +41,7 @@ pub struct WindowPreferences {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct EditorPreferences {
     pub confirm_delete: bool,
     pub auto_save: bool,
@@ -26,40 +50,90 @@ pub struct EditorPreferences {
     pub font_size: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExportPreferences {
     pub obsidian_vault_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppearancePreferences {
     pub theme: String, // "dark" | "light" | "auto"
 }
 
+/// What the menu bar icon reports about the notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrayPreferences {
+    pub show_total: bool,
+    pub show_pinned: bool,
+    pub show_created_today: bool,
+    pub show_sync_status: bool,
+    pub show_count_in_menu_bar: bool, // Total next to the icon, not just in the tooltip
+}
+
+impl Default for WindowPreferences {
+    fn default() -> Self {
+        Self {
+            default_width: 400.0,
+            default_height: 300.0,
+            transparency: 0.85,
+        }
+    }
+}
+
+impl Default for EditorPreferences {
+    fn default() -> Self {
+        Self {
+            confirm_delete: true,
+            auto_save: true,
+            auto_save_interval: 30,
+            font_family: "-apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif".to_string(),
+            font_size: 11,
+        }
+    }
+}
+
+impl Default for AppearancePreferences {
+    fn default() -> Self {
+        Self {
+            theme: "dark".to_string(),
+        }
+    }
+}
+
+impl Default for TrayPreferences {
+    fn default() -> Self {
+        Self {
+            show_total: true,
+            show_pinned: true,
+            show_created_today: false,
+            show_sync_status: true,
+            show_count_in_menu_bar: false,
+        }
+    }
+}
+
 impl Default for Preferences {
     fn default() -> Self {
         Self {
-            sync: SyncSettings {
-                icloud_sync_enabled: false,
-            },
-            window: WindowPreferences {
-                default_width: 400.0,
-                default_height: 300.0,
-                transparency: 0.85,
-            },
-            editor: EditorPreferences {
-                confirm_delete: true,
-                auto_save: true,
-                auto_save_interval: 30,
-                font_family: "-apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif".to_string(),
-                font_size: 11,
-            },
-            export: ExportPreferences {
-                obsidian_vault_path: None,
-            },
-            appearance: AppearancePreferences {
-                theme: "dark".to_string(),
-            },
+            version: CURRENT_VERSION,
+            sync: SyncSettings::default(),
+            window: WindowPreferences::default(),
+            editor: EditorPreferences::default(),
+            export: ExportPreferences::default(),
+            appearance: AppearancePreferences::default(),
+            tray: TrayPreferences::default(),
+            shortcuts: ShortcutPreferences::default(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Preferences as read from disk, with a note for the user when the file
+/// had to be repaired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedPreferences {
+    pub preferences: Preferences,
+    pub warning: Option<String>,
+}
@@ -1,8 +1,35 @@
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 use crate::core::error::{AppError, AppResult};
+use crate::features::shortcuts::ShortcutPreferences;
+use crate::features::sync::SyncSettings;
 use crate::infrastructure::storage::StoragePaths;
-use super::models::Preferences;
+use super::models::{
+    clamp_opacity, AppearancePreferences, EditorPreferences, ExportPreferences, LoadedPreferences,
+    Preferences, TrayPreferences, WindowPreferences, CURRENT_VERSION,
+};
+
+/// Upgrades a file one version at a time; entry `n` takes version `n + 1`
+/// to `n + 2`. Files written before versioning count as version 1.
+const MIGRATIONS: [fn(&mut Value); 1] = [migrate_v1_to_v2];
+
+/// The repair warning from launch, until the frontend has shown it. Managed as
+/// Tauri state.
+#[derive(Default)]
+pub struct PreferencesWarning(Mutex<Option<String>>);
+
+impl PreferencesWarning {
+    pub fn new(warning: Option<String>) -> Self {
+        Self(Mutex::new(warning))
+    }
+    
+    pub fn take(&self) -> Option<String> {
+        self.0.lock().unwrap().take()
+    }
+}
 
 pub struct PreferencesManager {
     path: PathBuf,
@@ -21,18 +48,184 @@ impl PreferencesManager {
     }
     
     pub fn load(&self) -> Preferences {
-        match fs::read_to_string(&self.path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Preferences::default(),
+        let loaded = self.load_checked();
+        if let Some(warning) = loaded.warning {
+            eprintln!("{}", warning);
         }
+        loaded.preferences
+    }
+    
+    /// Reads the file, migrating older versions and resetting only the fields
+    /// that can't be read. A repaired file is backed up before it is rewritten.
+    pub fn load_checked(&self) -> LoadedPreferences {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(_) => {
+                return LoadedPreferences {
+                    preferences: Preferences::default(),
+                    warning: None,
+                };
+            }
+        };
+        
+        let mut value: Value = match serde_json::from_str(&content) {
+            Ok(value) => value,
+            Err(e) => {
+                let preferences = Preferences::default();
+                let warning = self.repaired(
+                    &preferences,
+                    format!("Preferences could not be read ({}) and were reset to defaults.", e),
+                );
+                return LoadedPreferences { preferences, warning: Some(warning) };
+            }
+        };
+        
+        let migrated = migrate(&mut value);
+        let reset = reset_invalid_fields(&mut value);
+        
+        let (preferences, reset) = match serde_json::from_value::<Preferences>(value) {
+            Ok(preferences) => (preferences, reset),
+            Err(_) => (Preferences::default(), vec!["all settings".to_string()]),
+        };
+        
+        let warning = if !reset.is_empty() {
+            Some(self.repaired(
+                &preferences,
+                format!("Some preferences were invalid and were reset to defaults: {}.", reset.join(", ")),
+            ))
+        } else {
+            if migrated {
+                if let Err(e) = self.save(&preferences) {
+                    eprintln!("Failed to save migrated preferences: {}", e.message);
+                }
+            }
+            None
+        };
+        
+        LoadedPreferences { preferences, warning }
     }
     
     pub fn save(&self, prefs: &Preferences) -> AppResult<()> {
-        let json = serde_json::to_string_pretty(prefs)?;
+        let mut prefs = prefs.clone();
+        prefs.version = CURRENT_VERSION;
+        let json = serde_json::to_string_pretty(&prefs)?;
         
         fs::write(&self.path, json)
             .map_err(|e| AppError::new("PREFS_SAVE_ERROR", e.to_string()))?;
         
         Ok(())
     }
-}
\ No newline at end of file
+    
+    /// Keeps a copy of the damaged file next to it, writes the repaired
+    /// preferences and returns the warning for the user
+    fn repaired(&self, prefs: &Preferences, message: String) -> String {
+        let file_name = self.path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "preferences.json".to_string());
+        let backup = self.path.with_file_name(format!(
+            "{}.bak-{}",
+            file_name,
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        ));
+        
+        let backed_up = fs::copy(&self.path, &backup).is_ok();
+        if let Err(e) = self.save(prefs) {
+            eprintln!("Failed to save repaired preferences: {}", e.message);
+        }
+        
+        if backed_up {
+            format!("{} The original file was saved as {}.", message, backup.display())
+        } else {
+            message
+        }
+    }
+}
+
+/// Runs every migration the file hasn't seen yet. Returns whether anything ran.
+fn migrate(value: &mut Value) -> bool {
+    let version = value.get("version")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .unwrap_or(1)
+        .max(1);
+    if version >= CURRENT_VERSION {
+        return false;
+    }
+    
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(value);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), Value::from(CURRENT_VERSION));
+    }
+    true
+}
+
+/// Version 1 allowed any window transparency; floating notes now stay between
+/// the minimum and maximum opacity
+fn migrate_v1_to_v2(value: &mut Value) {
+    let transparency = value.pointer_mut("/window/transparency");
+    if let Some(transparency) = transparency {
+        if let Some(current) = transparency.as_f64() {
+            *transparency = Value::from(clamp_opacity(current));
+        }
+    }
+}
+
+/// Drops each field that doesn't deserialize so it takes its default, and a
+/// whole section when it isn't an object. Returns the paths that were reset.
+fn reset_invalid_fields(value: &mut Value) -> Vec<String> {
+    let mut reset = Vec::new();
+    let Some(object) = value.as_object_mut() else {
+        return reset;
+    };
+    
+    let sections = ["sync", "window", "editor", "export", "appearance", "tray", "shortcuts"];
+    for section in sections {
+        let Some(fields) = object.get_mut(section) else {
+            continue;
+        };
+        if section_parses(section, fields) {
+            continue;
+        }
+        
+        match fields.as_object_mut() {
+            Some(fields) => {
+                let invalid: Vec<String> = fields.iter()
+                    .filter(|(key, field)| {
+                        let single = Value::Object([((*key).clone(), (*field).clone())].into_iter().collect());
+                        !section_parses(section, &single)
+                    })
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                for key in invalid {
+                    fields.remove(&key);
+                    reset.push(format!("{}.{}", section, key));
+                }
+            }
+            None => {
+                object.remove(section);
+                reset.push(section.to_string());
+            }
+        }
+    }
+    
+    reset
+}
+
+fn section_parses(section: &str, value: &Value) -> bool {
+    match section {
+        "sync" => parses::<SyncSettings>(value),
+        "window" => parses::<WindowPreferences>(value),
+        "editor" => parses::<EditorPreferences>(value),
+        "export" => parses::<ExportPreferences>(value),
+        "appearance" => parses::<AppearancePreferences>(value),
+        "tray" => parses::<TrayPreferences>(value),
+        "shortcuts" => parses::<ShortcutPreferences>(value),
+        _ => true,
+    }
+}
+
+fn parses<T: DeserializeOwned>(value: &Value) -> bool {
+    T::deserialize(value).is_ok()
+}
@@ -0,0 +1,44 @@
+use uuid::Uuid;
+
+/// Selector ids are billiard ball numbers
+pub const MIN_BALL: i64 = 1;
+pub const MAX_BALL: i64 = 15;
+
+/// Solid ball colors from src/constants/billiardColors.ts; balls 9–15 are the
+/// striped versions of 1–7 and share their color
+const SOLID_COLORS: [&str; 8] = [
+    "#ffd700", // 1 - Or
+    "#4169e1", // 2 - Bleu Royal
+    "#dc143c", // 3 - Rouge Cramoisie
+    "#8b008b", // 4 - Magenta Foncé
+    "#ff8c00", // 5 - Orange Foncé
+    "#228b22", // 6 - Vert Forêt
+    "#8b0000", // 7 - Rouge Foncé
+    "#2f2f2f", // 8 - Noir
+];
+
+/// Every device gives a ball the same uuid, so ball 3 here is ball 3 on the
+/// others. The bytes are arbitrary but must never change.
+pub fn ball_uuid(id: i64) -> String {
+    let mut bytes = *b"extranuts-ball\0\0";
+    bytes[15] = id as u8;
+    Uuid::new_v8(bytes).to_string()
+}
+
+pub fn is_valid_ball(id: i64) -> bool {
+    (MIN_BALL..=MAX_BALL).contains(&id)
+}
+
+pub fn default_color(id: i64) -> &'static str {
+    let index = if id > 8 { id - 9 } else { id - 1 };
+    SOLID_COLORS[index.clamp(0, 7) as usize]
+}
+
+/// Names the UI shows before the user renames a ball
+pub fn default_name(id: i64) -> &'static str {
+    match id {
+        1 => "Trading",
+        2 => "Extranut",
+        _ => "",
+    }
+}
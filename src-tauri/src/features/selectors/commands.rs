@@ -1,5 +1,7 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 use crate::core::{AppState, error::AppResult};
+use crate::features::sync::watcher::sync_after_change;
+use crate::features::undo::UndoService;
 use super::models::*;
 use super::repository::SelectorRepository;
 
@@ -19,8 +21,20 @@ pub async fn update_selector_name(
     request: UpdateSelectorRequest,
 ) -> AppResult<()> {
     let db = state.db();
-    let repo = SelectorRepository::new(db);
-    repo.update_selector_name(&request)
+    let repo = SelectorRepository::new(db.clone());
+    let undo = UndoService::new(db, state.undo_history());
+    undo.record("Rename selector", || repo.update_selector_name(&request))
+}
+
+#[tauri::command]
+pub async fn update_selector_style(
+    state: State<'_, AppState>,
+    request: UpdateSelectorStyleRequest,
+) -> AppResult<Selector> {
+    let db = state.db();
+    let repo = SelectorRepository::new(db.clone());
+    let undo = UndoService::new(db, state.undo_history());
+    undo.record("Edit selector", || repo.update_selector_style(&request))
 }
 
 #[tauri::command]
@@ -33,6 +47,16 @@ pub async fn get_selector(
     repo.get_selector(id)
 }
 
+#[tauri::command]
+pub async fn get_selector_by_uuid(
+    state: State<'_, AppState>,
+    uuid: String,
+) -> AppResult<Option<Selector>> {
+    let db = state.db();
+    let repo = SelectorRepository::new(db);
+    repo.get_selector_by_uuid(&uuid)
+}
+
 #[tauri::command]
 pub async fn get_all_selectors(
     state: State<'_, AppState>,
@@ -44,12 +68,44 @@ pub async fn get_all_selectors(
 
 #[tauri::command]
 pub async fn delete_selector(
+    app: AppHandle,
     state: State<'_, AppState>,
     id: i64,
+    policy: Option<SelectorDeletePolicy>,
 ) -> AppResult<()> {
     let db = state.db();
-    let repo = SelectorRepository::new(db);
-    repo.delete_selector(id)
+    let repo = SelectorRepository::new(db.clone());
+    let undo = UndoService::new(db, state.undo_history());
+    let policy = policy.unwrap_or_default();
+    undo.record("Delete selector", || repo.delete_selector(id, &policy))?;
+    sync_after_change(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reassign_selector(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    from: i64,
+    to: i64,
+) -> AppResult<usize> {
+    let db = state.db();
+    let repo = SelectorRepository::new(db.clone());
+    let undo = UndoService::new(db, state.undo_history());
+    let reassigned = undo.record("Reassign selector", || repo.reassign_selector(from, to))?;
+    sync_after_change(&app);
+    Ok(reassigned)
+}
+
+#[tauri::command]
+pub async fn check_selector_integrity(
+    state: State<'_, AppState>,
+    repair: Option<SelectorRepair>,
+) -> AppResult<SelectorIntegrityReport> {
+    let db = state.db();
+    let repo = SelectorRepository::new(db.clone());
+    let undo = UndoService::new(db, state.undo_history());
+    undo.record("Repair selectors", || repo.check_integrity(repair))
 }
 
 #[tauri::command]
@@ -1,4 +1,5 @@
 pub mod models;
+pub mod balls;
 pub mod repository;
 pub mod commands;
 
@@ -4,7 +4,12 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Selector {
     pub id: i64,
+    pub uuid: String,
     pub name: String,
+    pub color: String,
+    pub icon: Option<String>,
+    pub shortcut: Option<String>,
+    pub note_count: i64,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -13,10 +18,54 @@ pub struct Selector {
 pub struct CreateSelectorRequest {
     pub id: i64,
     pub name: String,
+    pub color: Option<String>, // Defaults to the ball's color
+    pub icon: Option<String>,
+    pub shortcut: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateSelectorRequest {
     pub id: i64,
     pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSelectorStyleRequest {
+    pub id: i64,
+    pub color: String,
+    pub icon: Option<String>,
+    pub shortcut: Option<String>,
+}
+
+/// What happens to the notes of a deleted selector
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SelectorDeletePolicy {
+    /// Notes lose their selector
+    #[default]
+    Clear,
+    /// Notes move to another selector
+    Reassign { to: i64 },
+}
+
+/// How `check_selector_integrity` fixes notes that reference a missing selector
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelectorRepair {
+    /// Set `selector_id` to NULL on the affected notes
+    Clear,
+    /// Recreate the missing selectors with a default name
+    Restore,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DanglingSelector {
+    pub selector_id: i64,
+    pub note_ids: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectorIntegrityReport {
+    pub dangling: Vec<DanglingSelector>,
+    pub repaired: bool,
 }
\ No newline at end of file
@@ -1,10 +1,16 @@
-use rusqlite::{params, OptionalExtension};
+use rusqlite::{params, OptionalExtension, Row};
 use chrono::{DateTime, Utc};
 use std::sync::{Arc, Mutex};
 use crate::infrastructure::database::Database;
 use crate::core::error::{AppError, AppResult};
+use crate::features::journal::{ChangeJournal, ChangeEntity};
+use crate::features::categories::color::normalize_color;
+use super::balls::{self, is_valid_ball};
 use super::models::*;
 
+/// Set in `app_meta` once the ball set has been seeded
+const SEEDED_KEY: &str = "selectors_seeded";
+
 pub struct SelectorRepository {
     db: Arc<Mutex<Database>>,
 }
@@ -19,21 +25,63 @@ impl SelectorRepository {
         let conn = db.connection();
         let now = Utc::now();
         
+        Self::validate_ball(request.id)?;
+        let uuid = balls::ball_uuid(request.id);
+        let color = match request.color {
+            Some(ref color) => normalize_color(color)?,
+            None => balls::default_color(request.id).to_string(),
+        };
+        let shortcut = Self::validate_shortcut(conn, request.id, request.shortcut.as_deref())?;
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM selectors WHERE id = ?1)",
+            params![request.id],
+            |row| row.get(0)
+        )?;
+        if exists {
+            return Err(AppError::new(
+                "SELECTOR_DUPLICATE",
+                format!("Selector {} already exists", request.id),
+            ));
+        }
+        
         // Insert with specific ID (billiard ball number)
-        conn.execute(
-            "INSERT INTO selectors (id, name, created_at, updated_at) 
-             VALUES (?1, ?2, ?3, ?4)",
+        tx.execute(
+            "INSERT INTO selectors (id, uuid, name, color, icon, shortcut, created_at, updated_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 request.id,
+                uuid,
                 request.name,
+                color,
+                request.icon,
+                shortcut,
                 now.to_rfc3339(),
                 now.to_rfc3339()
             ],
         ).map_err(|e| AppError::new("SELECTOR_CREATE_ERROR", e.to_string()))?;
         
+        ChangeJournal::new(&tx, db.device_id()).record(ChangeEntity::Selector, request.id, None)?;
+        
+        // Notes may already carry this ball number
+        let note_count: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM notes WHERE selector_id = ?1",
+            params![request.id],
+            |row| row.get(0)
+        )?;
+        tx.commit()?;
+        
         Ok(Selector {
             id: request.id,
+            uuid,
             name: request.name.clone(),
+            color,
+            icon: request.icon.clone(),
+            shortcut,
+            note_count,
             created_at: now,
             updated_at: now,
         })
@@ -44,22 +92,29 @@ impl SelectorRepository {
         let conn = db.connection();
         
         let mut stmt = conn.prepare(
-            "SELECT id, name, created_at, updated_at 
+            "SELECT id, uuid, name, created_at, updated_at,
+                    (SELECT COUNT(*) FROM notes WHERE notes.selector_id = selectors.id),
+                    color, icon, shortcut
              FROM selectors WHERE id = ?1"
         )?;
         
-        let selector = stmt.query_row(params![id], |row| {
-            Ok(Selector {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            })
-        }).optional()?;
+        let selector = stmt.query_row(params![id], Self::map_row).optional()?;
+        
+        Ok(selector)
+    }
+    
+    pub fn get_selector_by_uuid(&self, uuid: &str) -> AppResult<Option<Selector>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT id, uuid, name, created_at, updated_at,
+                    (SELECT COUNT(*) FROM notes WHERE notes.selector_id = selectors.id),
+                    color, icon, shortcut
+             FROM selectors WHERE uuid = ?1"
+        )?;
+        
+        let selector = stmt.query_row(params![uuid], Self::map_row).optional()?;
         
         Ok(selector)
     }
@@ -69,22 +124,13 @@ impl SelectorRepository {
         let conn = db.connection();
         
         let mut stmt = conn.prepare(
-            "SELECT id, name, created_at, updated_at 
+            "SELECT id, uuid, name, created_at, updated_at,
+                    (SELECT COUNT(*) FROM notes WHERE notes.selector_id = selectors.id),
+                    color, icon, shortcut
              FROM selectors ORDER BY id"
         )?;
         
-        let selector_iter = stmt.query_map([], |row| {
-            Ok(Selector {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(2)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-                updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
-                    .unwrap()
-                    .with_timezone(&Utc),
-            })
-        })?;
+        let selector_iter = stmt.query_map([], Self::map_row)?;
         
         let mut selectors = Vec::new();
         for selector in selector_iter {
@@ -98,29 +144,61 @@ impl SelectorRepository {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         let now = Utc::now();
+        Self::validate_ball(request.id)?;
+        
+
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        let before = journal.snapshot(ChangeEntity::Selector, request.id)?;
         
         // Try to update first
-        let rows_affected = conn.execute(
+        let rows_affected = tx.execute(
             "UPDATE selectors SET name = ?1, updated_at = ?2 WHERE id = ?3",
             params![request.name, now.to_rfc3339(), request.id],
         )?;
         
         // If no rows were updated, insert a new record
         if rows_affected == 0 {
-            conn.execute(
-                "INSERT INTO selectors (id, name, created_at, updated_at) VALUES (?1, ?2, ?3, ?4)",
-                params![request.id, request.name, now.to_rfc3339(), now.to_rfc3339()],
+            tx.execute(
+                "INSERT INTO selectors (id, uuid, name, color, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    request.id,
+                    balls::ball_uuid(request.id),
+                    request.name,
+                    balls::default_color(request.id),
+                    now.to_rfc3339(),
+                    now.to_rfc3339()
+                ],
             )?;
         }
         
+        journal.record(ChangeEntity::Selector, request.id, before)?;
+        tx.commit()?;
+        
         Ok(())
     }
     
-    pub fn delete_selector(&self, id: i64) -> AppResult<()> {
+    pub fn delete_selector(&self, id: i64, policy: &SelectorDeletePolicy) -> AppResult<()> {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
         
-        let rows_affected = conn.execute(
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let to = match *policy {
+            SelectorDeletePolicy::Clear => None,
+            SelectorDeletePolicy::Reassign { to } => {
+                Self::ensure_target(&tx, id, to)?;
+                Some(to)
+            }
+        };
+        Self::move_notes(&tx, &journal, id, to)?;
+        
+        let before = journal.snapshot(ChangeEntity::Selector, id)?;
+        
+        let rows_affected = tx.execute(
             "DELETE FROM selectors WHERE id = ?1",
             params![id],
         )?;
@@ -129,9 +207,239 @@ impl SelectorRepository {
             return Err(AppError::new("SELECTOR_NOT_FOUND", format!("Selector with id {} not found", id)));
         }
         
+        journal.record(ChangeEntity::Selector, id, before)?;
+        tx.commit()?;
+        
+        Ok(())
+    }
+    
+    /// Sets the color, icon and keyboard shortcut of a selector
+    pub fn update_selector_style(&self, request: &UpdateSelectorStyleRequest) -> AppResult<Selector> {
+        {
+            let db = self.db.lock().unwrap();
+            let conn = db.connection();
+            
+            let color = normalize_color(&request.color)?;
+            let shortcut = Self::validate_shortcut(conn, request.id, request.shortcut.as_deref())?;
+            
+            let tx = conn.unchecked_transaction()
+                .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+            let journal = ChangeJournal::new(&tx, db.device_id());
+            let before = journal.snapshot(ChangeEntity::Selector, request.id)?;
+            
+            let rows_affected = tx.execute(
+                "UPDATE selectors SET color = ?1, icon = ?2, shortcut = ?3, updated_at = ?4 WHERE id = ?5",
+                params![color, request.icon, shortcut, Utc::now().to_rfc3339(), request.id],
+            )?;
+            
+            if rows_affected == 0 {
+                return Err(AppError::new("SELECTOR_NOT_FOUND", format!("Selector with id {} not found", request.id)));
+            }
+            
+            journal.record(ChangeEntity::Selector, request.id, before)?;
+            tx.commit()?;
+        }
+        
+        self.get_selector(request.id)?
+            .ok_or_else(|| AppError::new("SELECTOR_NOT_FOUND", format!("Selector with id {} not found", request.id)))
+    }
+    
+    /// Creates the ball set with its default names and colors the first time
+    /// the database is opened. Balls that already exist get their shared uuid
+    /// and, for older rows, their ball's color. Runs only once so a ball the
+    /// user deleted stays deleted.
+    pub fn seed_defaults(&self) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        let now = Utc::now().to_rfc3339();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        
+        let seeded: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM app_meta WHERE key = ?1)",
+            params![SEEDED_KEY],
+            |row| row.get(0)
+        )?;
+        if seeded {
+            return Ok(());
+        }
+        
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        for id in balls::MIN_BALL..=balls::MAX_BALL {
+            let uuid = balls::ball_uuid(id);
+            let before = journal.snapshot(ChangeEntity::Selector, id)?;
+            
+            let changed = match before {
+                None => tx.execute(
+                    "INSERT INTO selectors (id, uuid, name, color, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                    params![id, uuid, balls::default_name(id), balls::default_color(id), now],
+                )?,
+                Some(_) => tx.execute(
+                    "UPDATE selectors SET uuid = ?1, color = COALESCE(color, ?2)
+                     WHERE id = ?3 AND (uuid IS NOT ?1 OR color IS NULL)",
+                    params![uuid, balls::default_color(id), id],
+                )?,
+            };
+            if changed > 0 {
+                journal.record(ChangeEntity::Selector, id, before)?;
+            }
+        }
+        
+        tx.execute(
+            "INSERT INTO app_meta (key, value) VALUES (?1, ?2)",
+            params![SEEDED_KEY, now],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+    
+    fn validate_ball(id: i64) -> AppResult<()> {
+        if !is_valid_ball(id) {
+            return Err(AppError::new(
+                "SELECTOR_OUT_OF_RANGE",
+                format!("Selector id must be between {} and {}, got {}", balls::MIN_BALL, balls::MAX_BALL, id),
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Trims the shortcut and makes sure no other selector uses it
+    fn validate_shortcut(conn: &rusqlite::Connection, id: i64, shortcut: Option<&str>) -> AppResult<Option<String>> {
+        let shortcut = match shortcut.map(str::trim) {
+            Some(shortcut) if !shortcut.is_empty() => shortcut.to_string(),
+            _ => return Ok(None),
+        };
+        
+        let owner: Option<i64> = conn.query_row(
+            "SELECT id FROM selectors WHERE shortcut = ?1 AND id != ?2",
+            params![shortcut, id],
+            |row| row.get(0)
+        ).optional()?;
+        
+        if let Some(owner) = owner {
+            return Err(AppError::new(
+                "SELECTOR_DUPLICATE_SHORTCUT",
+                format!("Shortcut '{}' is already used by selector {}", shortcut, owner),
+            ));
+        }
+        
+        Ok(Some(shortcut))
+    }
+    
+    /// Moves every note from selector `from` to `to`, returning how many moved.
+    /// `from` doesn't need to exist, so dangling references can be fixed too.
+    pub fn reassign_selector(&self, from: i64, to: i64) -> AppResult<usize> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        Self::ensure_target(&tx, from, to)?;
+        let moved = Self::move_notes(&tx, &journal, from, Some(to))?;
+        
+        tx.commit()?;
+        Ok(moved)
+    }
+    
+    /// Finds notes whose `selector_id` has no selector row, optionally repairing them
+    pub fn check_integrity(&self, repair: Option<SelectorRepair>) -> AppResult<SelectorIntegrityReport> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let rows = tx.prepare(
+            "SELECT n.selector_id, n.id FROM notes n
+             WHERE n.selector_id IS NOT NULL
+               AND NOT EXISTS (SELECT 1 FROM selectors s WHERE s.id = n.selector_id)
+             ORDER BY n.selector_id, n.id"
+        )?
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        let mut dangling: Vec<DanglingSelector> = Vec::new();
+        for (selector_id, note_id) in rows {
+            match dangling.last_mut() {
+                Some(entry) if entry.selector_id == selector_id => entry.note_ids.push(note_id),
+                _ => dangling.push(DanglingSelector { selector_id, note_ids: vec![note_id] }),
+            }
+        }
+        
+        let repaired = repair.is_some() && !dangling.is_empty();
+        if let Some(repair) = repair {
+            let now = Utc::now().to_rfc3339();
+            for entry in &dangling {
+                match repair {
+                    SelectorRepair::Restore if is_valid_ball(entry.selector_id) => {
+                        tx.execute(
+                            "INSERT INTO selectors (id, uuid, name, color, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+                            params![
+                                entry.selector_id,
+                                balls::ball_uuid(entry.selector_id),
+                                balls::default_name(entry.selector_id),
+                                balls::default_color(entry.selector_id),
+                                now
+                            ],
+                        )?;
+                        journal.record(ChangeEntity::Selector, entry.selector_id, None)?;
+                    }
+                    // Numbers outside the ball set can't be restored, only cleared
+                    SelectorRepair::Restore | SelectorRepair::Clear => {
+                        Self::move_notes(&tx, &journal, entry.selector_id, None)?;
+                    }
+                }
+            }
+        }
+        
+        tx.commit()?;
+        Ok(SelectorIntegrityReport { dangling, repaired })
+    }
+    
+    fn ensure_target(conn: &rusqlite::Connection, from: i64, to: i64) -> AppResult<()> {
+        if from == to {
+            return Err(AppError::new("VALIDATION_ERROR", "Cannot reassign a selector to itself"));
+        }
+        
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM selectors WHERE id = ?1)",
+            params![to],
+            |row| row.get(0)
+        )?;
+        if !exists {
+            return Err(AppError::new("SELECTOR_NOT_FOUND", format!("Selector with id {} not found", to)));
+        }
+        
         Ok(())
     }
     
+    fn move_notes(
+        conn: &rusqlite::Connection,
+        journal: &ChangeJournal,
+        from: i64,
+        to: Option<i64>,
+    ) -> AppResult<usize> {
+        let note_ids = conn.prepare("SELECT id FROM notes WHERE selector_id = ?1")?
+            .query_map(params![from], |row| row.get::<_, i64>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        for note_id in &note_ids {
+            let before = journal.snapshot(ChangeEntity::Note, *note_id)?;
+            conn.execute(
+                "UPDATE notes SET selector_id = ?1 WHERE id = ?2",
+                params![to, note_id],
+            )?;
+            journal.record(ChangeEntity::Note, *note_id, before)?;
+        }
+        
+        Ok(note_ids.len())
+    }
+    
     pub fn selector_exists(&self, id: i64) -> AppResult<bool> {
         let db = self.db.lock().unwrap();
         let conn = db.connection();
@@ -144,4 +452,26 @@ impl SelectorRepository {
         
         Ok(count > 0)
     }
+    
+    fn map_row(row: &Row) -> rusqlite::Result<Selector> {
+        let id: i64 = row.get(0)?;
+        let color: Option<String> = row.get(6)?;
+        
+        Ok(Selector {
+            id,
+            uuid: row.get(1)?,
+            name: row.get(2)?,
+            // Rows synced from an older version may not carry a color yet
+            color: color.unwrap_or_else(|| balls::default_color(id).to_string()),
+            icon: row.get(7)?,
+            shortcut: row.get(8)?,
+            note_count: row.get(5)?,
+            created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(3)?)
+                .unwrap()
+                .with_timezone(&Utc),
+            updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(4)?)
+                .unwrap()
+                .with_timezone(&Utc),
+        })
+    }
 }
\ No newline at end of file
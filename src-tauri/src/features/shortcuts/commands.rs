@@ -0,0 +1,27 @@
+use tauri::AppHandle;
+use crate::features::preferences::PreferencesManager;
+use super::models::ShortcutPreferences;
+use super::service::ShortcutService;
+
+/// Rebinds the global shortcuts immediately and saves them to preferences
+#[tauri::command]
+pub fn update_shortcuts(
+    app: AppHandle,
+    shortcuts: ShortcutPreferences,
+) -> Result<(), String> {
+    ShortcutService::apply(&app, &shortcuts)
+        .map_err(|e| e.message)?;
+    
+    let manager = PreferencesManager::new(&app);
+    let mut prefs = manager.load();
+    prefs.shortcuts = shortcuts;
+    manager.save(&prefs)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn validate_shortcut(accelerator: String) -> Result<(), String> {
+    ShortcutService::parse(&accelerator)
+        .map(|_| ())
+        .map_err(|e| e.message)
+}
@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+
+/// Global hotkeys as Tauri accelerators, e.g. "CmdOrCtrl+Shift+N". `None`
+/// leaves the action without a shortcut.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutPreferences {
+    pub new_note: Option<String>,
+    pub quick_search: Option<String>,
+    pub quick_capture: Option<String>,
+    pub toggle_main_window: Option<String>,
+}
+
+impl Default for ShortcutPreferences {
+    fn default() -> Self {
+        Self {
+            new_note: Some("CmdOrCtrl+Shift+N".to_string()),
+            quick_search: Some("CmdOrCtrl+Shift+F".to_string()),
+            quick_capture: Some("CmdOrCtrl+Shift+Space".to_string()),
+            toggle_main_window: None,
+        }
+    }
+}
+
+impl ShortcutPreferences {
+    pub fn bindings(&self) -> Vec<(ShortcutAction, Option<&str>)> {
+        vec![
+            (ShortcutAction::NewNote, self.new_note.as_deref()),
+            (ShortcutAction::QuickSearch, self.quick_search.as_deref()),
+            (ShortcutAction::QuickCapture, self.quick_capture.as_deref()),
+            (ShortcutAction::ToggleMainWindow, self.toggle_main_window.as_deref()),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    NewNote,
+    QuickSearch,
+    QuickCapture,
+    ToggleMainWindow,
+}
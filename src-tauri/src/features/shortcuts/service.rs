@@ -0,0 +1,134 @@
+use std::str::FromStr;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutEvent, ShortcutState};
+use crate::core::error::{AppError, AppResult};
+use crate::features::windows::service::WindowService;
+use super::models::{ShortcutAction, ShortcutPreferences};
+
+/// Accelerators the app menus already use; a global hotkey would swallow them
+const RESERVED: [&str; 2] = ["CmdOrCtrl+,", "CmdOrCtrl+Q"];
+
+/// Which action each registered hotkey triggers. Managed as Tauri state.
+#[derive(Default)]
+pub struct ShortcutRegistry {
+    bindings: Mutex<Vec<(ShortcutAction, Shortcut)>>,
+}
+
+pub struct ShortcutService;
+
+impl ShortcutService {
+    /// Parses every configured shortcut, rejecting invalid accelerators,
+    /// bare keys, reserved menu shortcuts and two actions on the same keys
+    pub fn validate(prefs: &ShortcutPreferences) -> AppResult<Vec<(ShortcutAction, Shortcut)>> {
+        let reserved: Vec<Shortcut> = RESERVED.iter()
+            .filter_map(|accelerator| Shortcut::from_str(accelerator).ok())
+            .collect();
+        let mut parsed: Vec<(ShortcutAction, Shortcut)> = Vec::new();
+        
+        for (action, accelerator) in prefs.bindings() {
+            let accelerator = match accelerator.map(str::trim) {
+                Some(accelerator) if !accelerator.is_empty() => accelerator,
+                _ => continue,
+            };
+            
+            let shortcut = Self::parse(accelerator)?;
+            
+            if reserved.contains(&shortcut) {
+                return Err(AppError::new(
+                    "SHORTCUT_CONFLICT",
+                    format!("'{}' is already used by the app menu", accelerator),
+                ));
+            }
+            if let Some((other, _)) = parsed.iter().find(|(_, existing)| *existing == shortcut) {
+                return Err(AppError::new(
+                    "SHORTCUT_CONFLICT",
+                    format!("'{}' is assigned to both {:?} and {:?}", accelerator, other, action),
+                ));
+            }
+            
+            parsed.push((action, shortcut));
+        }
+        
+        Ok(parsed)
+    }
+    
+    pub fn parse(accelerator: &str) -> AppResult<Shortcut> {
+        let shortcut = Shortcut::from_str(accelerator)
+            .map_err(|e| AppError::new("SHORTCUT_INVALID", format!("Invalid shortcut '{}': {}", accelerator, e)))?;
+        
+        // A global hotkey without a modifier would hijack normal typing
+        if shortcut.mods.is_empty() || shortcut.mods == Modifiers::SHIFT {
+            return Err(AppError::new(
+                "SHORTCUT_INVALID",
+                format!("Shortcut '{}' needs Cmd, Ctrl or Option", accelerator),
+            ));
+        }
+        
+        Ok(shortcut)
+    }
+    
+    /// Replaces the registered hotkeys with `prefs`. If the system refuses one
+    /// (usually because another app owns it) the previous set is restored.
+    pub fn apply(app: &AppHandle, prefs: &ShortcutPreferences) -> AppResult<()> {
+        let shortcuts = Self::validate(prefs)?;
+        let registry = app.state::<ShortcutRegistry>();
+        let global = app.global_shortcut();
+        
+        let previous = std::mem::take(&mut *registry.bindings.lock().unwrap());
+        global.unregister_all()
+            .map_err(|e| AppError::new("SHORTCUT_REGISTER_ERROR", e.to_string()))?;
+        
+        for (_, shortcut) in &shortcuts {
+            if let Err(e) = global.register(*shortcut) {
+                let _ = global.unregister_all();
+                Self::register_all(app, previous);
+                return Err(AppError::new(
+                    "SHORTCUT_UNAVAILABLE",
+                    format!("'{}' could not be registered, it may be used by another app: {}", shortcut, e),
+                ));
+            }
+        }
+        
+        *registry.bindings.lock().unwrap() = shortcuts;
+        Ok(())
+    }
+    
+    /// Called by the global shortcut plugin for every registered hotkey
+    pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+        if event.state != ShortcutState::Pressed {
+            return;
+        }
+        
+        let action = app.state::<ShortcutRegistry>()
+            .bindings.lock().unwrap()
+            .iter()
+            .find(|(_, bound)| bound == shortcut)
+            .map(|(action, _)| *action);
+        
+        let result = match action {
+            Some(ShortcutAction::NewNote) => WindowService::request_from_main(app, "new-note-requested"),
+            Some(ShortcutAction::QuickSearch) => WindowService::request_from_main(app, "quick-search-requested"),
+            Some(ShortcutAction::QuickCapture) => WindowService::open_quick_capture(app),
+            Some(ShortcutAction::ToggleMainWindow) => {
+                WindowService::toggle_main_window(app);
+                Ok(())
+            }
+            None => Ok(()),
+        };
+        
+        if let Err(e) = result {
+            eprintln!("Shortcut {} failed: {}", shortcut, e.message);
+        }
+    }
+    
+    /// Best-effort rollback; anything that fails to register again is dropped
+    fn register_all(app: &AppHandle, shortcuts: Vec<(ShortcutAction, Shortcut)>) {
+        let global = app.global_shortcut();
+        let registered = shortcuts.into_iter()
+            .filter(|(_, shortcut)| global.register(*shortcut).is_ok())
+            .collect();
+        
+        *app.state::<ShortcutRegistry>().bindings.lock().unwrap() = registered;
+    }
+}
@@ -1,13 +1,18 @@
 use tauri::{State, AppHandle};
 use crate::core::AppState;
 use super::service::SyncService;
+use super::folder::FolderSyncReport;
+use super::models::SyncStatus;
+use super::watcher::FolderWatcher;
+use crate::features::windows::tray::refresh_tray;
 
 #[tauri::command]
 pub fn get_sync_status(
     app: AppHandle,
-) -> Result<bool, String> {
+    state: State<AppState>,
+) -> Result<SyncStatus, String> {
     let service = SyncService::new(app);
-    service.get_sync_status()
+    service.get_sync_status(state.db())
         .map_err(|e| e.message)
 }
 
@@ -17,15 +22,61 @@ pub fn toggle_icloud_sync(
     state: State<AppState>,
     enabled: bool,
 ) -> Result<String, String> {
-    let service = SyncService::new(app);
+    let service = SyncService::new(app.clone());
     let current_db = state.db();
     
     match service.toggle_icloud_sync(enabled, current_db) {
         Ok((message, new_db)) => {
             // Replace the database in the app state
             *state.db.lock().unwrap() = new_db;
+            refresh_tray(&app);
             Ok(message)
         }
         Err(e) => Err(e.message)
     }
+}
+
+#[tauri::command]
+pub fn configure_folder_sync(
+    app: AppHandle,
+    state: State<AppState>,
+    watcher: State<FolderWatcher>,
+    enabled: bool,
+    path: Option<String>,
+) -> Result<Option<FolderSyncReport>, String> {
+    let service = SyncService::new(app.clone());
+    let folder = service.configure_folder_sync(enabled, path)
+        .map_err(|e| e.message)?;
+    
+    watcher.stop();
+    
+    match folder {
+        Some(folder) => {
+            let report = service.run_folder_sync(state.db(), folder.clone())
+                .map_err(|e| e.message)?;
+            watcher.start(&app, folder)
+                .map_err(|e| e.message)?;
+            refresh_tray(&app);
+            Ok(Some(report))
+        }
+        None => {
+            refresh_tray(&app);
+            Ok(None)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn sync_folder_now(
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<FolderSyncReport, String> {
+    let service = SyncService::new(app.clone());
+    let folder = service.folder_sync_path()
+        .ok_or_else(|| "Folder sync is not enabled".to_string())?;
+    
+    let report = service.run_folder_sync(state.db(), folder)
+        .map_err(|e| e.message)?;
+    refresh_tray(&app);
+    Ok(report)
 }
\ No newline at end of file
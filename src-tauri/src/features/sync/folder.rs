@@ -0,0 +1,774 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::database::Database;
+use crate::features::journal::{ChangeJournal, ChangeEntity};
+use super::note_file::{content_hash, NoteFile};
+
+/// Mirrors notes as one Markdown file each in a plain directory.
+///
+/// The `sync_files` table remembers the hash of every file as of the last sync
+/// with this folder, which tells us which side changed since then. When both
+/// sides changed, the most recently updated version wins and the other one is
+/// written next to it as a conflict copy, which the next sync imports.
+pub struct FolderSync {
+    db: Arc<Mutex<Database>>,
+    root: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FolderSyncReport {
+    pub exported: usize,       // files written from local notes
+    pub imported: usize,       // notes created from new files
+    pub updated_local: usize,  // notes updated from edited files
+    pub deleted_local: usize,  // notes deleted because their file was removed
+    pub deleted_remote: usize, // files removed because their note was deleted
+    pub conflicts: usize,      // both sides changed, newest kept and the other copied
+    pub errors: Vec<String>,
+}
+
+struct LocalNote {
+    id: i64,
+    updated_at: String, // As stored, to spot edits made while the sync runs
+    file: NoteFile,
+}
+
+struct RemoteFile {
+    path: PathBuf,
+    text: String,
+    file: NoteFile,
+    modified: DateTime<Utc>,
+}
+
+impl RemoteFile {
+    /// External editors rarely touch the frontmatter, so the file's mtime
+    /// is the better clock when it is newer
+    fn updated_at(&self) -> DateTime<Utc> {
+        match self.file.updated_at {
+            Some(updated_at) if updated_at > self.modified => updated_at,
+            _ => self.modified,
+        }
+    }
+}
+
+struct SyncRecord {
+    file_name: String,
+    content_hash: String,
+}
+
+/// What the folder holds right now
+struct FolderScan {
+    by_uuid: HashMap<String, RemoteFile>,
+    untracked: Vec<RemoteFile>,
+    evicted: HashSet<String>, // Files iCloud replaced with a `.Name.md.icloud` placeholder
+}
+
+impl FolderScan {
+    fn is_empty(&self) -> bool {
+        self.by_uuid.is_empty() && self.untracked.is_empty() && self.evicted.is_empty()
+    }
+}
+
+impl FolderSync {
+    /// Creates the folder the first time. A folder we already synced with
+    /// that has disappeared is more likely an unmounted drive than a wipe,
+    /// so it is reported rather than recreated empty.
+    pub fn new(db: Arc<Mutex<Database>>, root: PathBuf) -> AppResult<Self> {
+        let sync = Self { db, root };
+
+        if !sync.root.exists() {
+            if sync.has_records()? {
+                return Err(AppError::new(
+                    "SYNC_FOLDER_MISSING",
+                    format!("The sync folder {} is missing", sync.root.display()),
+                ));
+            }
+            fs::create_dir_all(&sync.root)
+                .map_err(|e| AppError::new("SYNC_FOLDER_ERROR", format!("Cannot create sync folder: {}", e)))?;
+        }
+
+        Ok(sync)
+    }
+
+    /// The database is only locked for each read or write, not for the file
+    /// work in between, so commands aren't blocked while the folder syncs
+    pub fn sync(&self) -> AppResult<FolderSyncReport> {
+        let mut report = FolderSyncReport::default();
+
+        let scan = self.scan_folder(&mut report)?;
+        let (local_notes, mut records) = {
+            let db = self.db.lock().unwrap();
+            (self.load_local_notes(db.connection())?, self.load_records(db.connection())?)
+        };
+
+        // An empty folder we synced with before was wiped or hasn't been
+        // restored yet; that is no reason to delete every note, so start over
+        // and export them all again
+        if scan.is_empty() && !records.is_empty() {
+            self.forget_records()?;
+            records.clear();
+        }
+
+        let FolderScan { by_uuid: mut remote_by_uuid, untracked: untracked_files, evicted } = scan;
+        let mut claimed_names: HashSet<String> = evicted.clone();
+
+        for note in local_notes {
+            let uuid = note.file.uuid.clone().unwrap_or_default();
+            let local_text = note.file.render();
+            let local_hash = content_hash(&local_text);
+            let record = records.remove(&uuid);
+
+            let result = match remote_by_uuid.remove(&uuid) {
+                None => match record {
+                    // Still in the folder, just not downloaded; sync it once it is
+                    Some(ref record) if evicted.contains(&record.file_name) => Ok(()),
+                    // Unchanged here and gone from the folder: deleted on the other side
+                    Some(ref record) if record.content_hash == local_hash => {
+                        self.delete_local_note(&note, &uuid)
+                            .map(|deleted| report.deleted_local += deleted as usize)
+                    }
+                    _ => {
+                        self.write_file(&uuid, &note.file, None, &mut claimed_names)
+                            .map(|_| report.exported += 1)
+                    }
+                },
+                Some(remote) => {
+                    let remote_hash = content_hash(&remote.text);
+                    let base_hash = record.as_ref().map(|r| r.content_hash.as_str());
+                    let local_changed = base_hash != Some(local_hash.as_str());
+                    let remote_changed = base_hash != Some(remote_hash.as_str());
+
+                    if remote_hash == local_hash {
+                        claimed_names.insert(file_name(&remote.path));
+                        self.save_record(&uuid, &file_name(&remote.path), &local_hash)
+                    } else if local_changed && !remote_changed {
+                        self.write_file(&uuid, &note.file, Some(&remote.path), &mut claimed_names)
+                            .map(|_| report.exported += 1)
+                    } else if remote_changed && !local_changed {
+                        self.apply_remote(Some(&note), &remote, &mut claimed_names)
+                            .map(|applied| report.updated_local += applied as usize)
+                    } else {
+                        report.conflicts += 1;
+                        if remote.updated_at() > note.file.updated_at.unwrap_or_default() {
+                            self.write_conflict_copy(&note.file, &mut claimed_names)
+                                .and_then(|_| self.apply_remote(Some(&note), &remote, &mut claimed_names))
+                                .map(|applied| report.updated_local += applied as usize)
+                        } else {
+                            self.write_conflict_copy(&remote.file, &mut claimed_names)
+                                .and_then(|_| self.write_file(&uuid, &note.file, Some(&remote.path), &mut claimed_names))
+                                .map(|_| report.exported += 1)
+                        }
+                    }
+                }
+            };
+
+            if let Err(e) = result {
+                report.errors.push(format!("{}: {}", note.file.title, e.message));
+            }
+        }
+
+        // Files whose note no longer exists locally
+        for (uuid, remote) in remote_by_uuid {
+            let result = match records.remove(&uuid) {
+                Some(record) if record.content_hash == content_hash(&remote.text) => {
+                    fs::remove_file(&remote.path)
+                        .map_err(AppError::from)
+                        .and_then(|_| self.delete_record(&uuid))
+                        .map(|_| report.deleted_remote += 1)
+                }
+                // New file, or edited after the note was deleted here
+                _ => self.apply_remote(None, &remote, &mut claimed_names)
+                    .map(|applied| report.imported += applied as usize),
+            };
+
+            if let Err(e) = result {
+                report.errors.push(format!("{}: {}", file_name(&remote.path), e.message));
+            }
+        }
+
+        // Plain Markdown files dropped into the folder
+        for mut remote in untracked_files {
+            remote.file.uuid = Some(Uuid::new_v4().to_string());
+            if let Err(e) = self.apply_remote(None, &remote, &mut claimed_names) {
+                report.errors.push(format!("{}: {}", file_name(&remote.path), e.message));
+            } else {
+                report.imported += 1;
+            }
+        }
+
+        // Both note and file are gone
+        for uuid in records.keys() {
+            self.delete_record(uuid)?;
+        }
+
+        Ok(report)
+    }
+
+    fn load_local_notes(&self, conn: &Connection) -> AppResult<Vec<LocalNote>> {
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.uuid, n.title, n.content, c.uuid, n.selector_id, n.is_pinned, n.created_at, n.updated_at
+             FROM notes n LEFT JOIN categories c ON c.id = n.category_id"
+        )?;
+
+        let notes = stmt.query_map([], |row| {
+            Ok(LocalNote {
+                id: row.get(0)?,
+                updated_at: row.get(8)?,
+                file: NoteFile {
+                    uuid: row.get(1)?,
+                    title: row.get(2)?,
+                    content: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                    category_uuid: row.get(4)?,
+                    selector_id: row.get(5)?,
+                    is_pinned: row.get::<_, i32>(6)? != 0,
+                    created_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(7)?)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&Utc)),
+                    updated_at: DateTime::parse_from_rfc3339(&row.get::<_, String>(8)?)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&Utc)),
+                },
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(notes)
+    }
+
+    /// Records of the last sync with this folder. Records left by another
+    /// folder say nothing about this one and are dropped.
+    fn load_records(&self, conn: &Connection) -> AppResult<HashMap<String, SyncRecord>> {
+        let folder = self.folder_key();
+        conn.execute("DELETE FROM sync_files WHERE folder IS NOT ?1", params![folder])?;
+
+        let mut stmt = conn.prepare("SELECT note_uuid, file_name, content_hash FROM sync_files WHERE folder = ?1")?;
+
+        let records = stmt.query_map(params![folder], |row| {
+            Ok((row.get::<_, String>(0)?, SyncRecord {
+                file_name: row.get(1)?,
+                content_hash: row.get(2)?,
+            }))
+        })?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+
+        Ok(records)
+    }
+
+    fn has_records(&self) -> AppResult<bool> {
+        let exists = self.db.lock().unwrap().connection().query_row(
+            "SELECT EXISTS(SELECT 1 FROM sync_files WHERE folder = ?1)",
+            params![self.folder_key()],
+            |row| row.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Reads every `.md` file, split into files we can match by uuid and files
+    /// we can't, and notes which files iCloud has evicted
+    fn scan_folder(&self, report: &mut FolderSyncReport) -> AppResult<FolderScan> {
+        let mut by_uuid = HashMap::new();
+        let mut untracked = Vec::new();
+        let mut evicted = HashSet::new();
+
+        let entries = fs::read_dir(&self.root)
+            .map_err(|e| AppError::new("SYNC_FOLDER_ERROR", format!("Cannot read sync folder: {}", e)))?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = file_name(&path);
+            if let Some(original) = evicted_name(&name) {
+                evicted.insert(original);
+                continue;
+            }
+            if name.starts_with('.') || path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    report.errors.push(format!("{}: {}", name, e));
+                    continue;
+                }
+            };
+            let modified = entry.metadata()
+                .and_then(|m| m.modified())
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_else(|_| Utc::now());
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("Untitled").to_string();
+
+            let remote = RemoteFile {
+                file: NoteFile::parse(&text, &stem),
+                path,
+                text,
+                modified,
+            };
+
+            match remote.file.uuid.clone() {
+                // A copied file shares its uuid with the original; import it as a new note
+                Some(uuid) if !by_uuid.contains_key(&uuid) => {
+                    by_uuid.insert(uuid, remote);
+                }
+                _ => untracked.push(remote),
+            }
+        }
+
+        Ok(FolderScan { by_uuid, untracked, evicted })
+    }
+
+    /// Writes the canonical file for a note, renaming it when the title changed
+    fn write_file(
+        &self,
+        uuid: &str,
+        file: &NoteFile,
+        current_path: Option<&Path>,
+        claimed_names: &mut HashSet<String>,
+    ) -> AppResult<()> {
+        let name = self.pick_file_name(uuid, &file.title, current_path, claimed_names);
+        let path = self.root.join(&name);
+        let text = file.render();
+
+        write_atomically(&path, &text)
+            .map_err(|e| AppError::new("SYNC_WRITE_ERROR", e.to_string()))?;
+
+        if let Some(old_path) = current_path {
+            if old_path != path {
+                fs::remove_file(old_path).ok();
+            }
+        }
+
+        claimed_names.insert(name.clone());
+        self.save_record(uuid, &name, &content_hash(&text))
+    }
+
+    /// Keeps the losing side of a conflict as `Title (conflict).md` under a new
+    /// uuid. It has no sync record, so the next sync imports it as a note.
+    fn write_conflict_copy(&self, file: &NoteFile, claimed_names: &mut HashSet<String>) -> AppResult<()> {
+        let uuid = Uuid::new_v4().to_string();
+        let copy = NoteFile {
+            uuid: Some(uuid.clone()),
+            title: format!("{} (conflict)", file.title),
+            ..file.clone()
+        };
+        let name = self.pick_file_name(&uuid, &copy.title, None, claimed_names);
+
+        write_atomically(&self.root.join(&name), &copy.render())
+            .map_err(|e| AppError::new("SYNC_WRITE_ERROR", e.to_string()))?;
+
+        claimed_names.insert(name);
+        Ok(())
+    }
+
+    /// Creates or updates the local note from a file, then rewrites the file
+    /// in canonical form so both sides hash the same. Returns `false` when the
+    /// note was edited in the app meanwhile; the next sync reconciles it.
+    fn apply_remote(
+        &self,
+        local: Option<&LocalNote>,
+        remote: &RemoteFile,
+        claimed_names: &mut HashSet<String>,
+    ) -> AppResult<bool> {
+        let uuid = remote.file.uuid.clone()
+            .ok_or_else(|| AppError::new("SYNC_ERROR", "File has no uuid"))?;
+        let updated_at = remote.updated_at();
+        let created_at = remote.file.created_at
+            .or(local.and_then(|n| n.file.created_at))
+            .unwrap_or(updated_at);
+
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+
+        let category_id: Option<i64> = match remote.file.category_uuid {
+            Some(ref category_uuid) => tx.query_row(
+                "SELECT id FROM categories WHERE uuid = ?1",
+                params![category_uuid],
+                |row| row.get(0)
+            ).optional()?,
+            None => None,
+        };
+
+        match local {
+            Some(note) => {
+                let before = journal.snapshot(ChangeEntity::Note, note.id)?;
+                let updated = tx.execute(
+                    "UPDATE notes SET title = ?1, content = ?2, category_id = ?3, selector_id = ?4, is_pinned = ?5, updated_at = ?6
+                     WHERE id = ?7 AND updated_at = ?8",
+                    params![
+                        remote.file.title,
+                        remote.file.content,
+                        category_id,
+                        remote.file.selector_id,
+                        remote.file.is_pinned as i32,
+                        updated_at.to_rfc3339(),
+                        note.id,
+                        note.updated_at
+                    ],
+                )?;
+                if updated == 0 {
+                    return Ok(false);
+                }
+                journal.record(ChangeEntity::Note, note.id, before)?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO notes (uuid, title, content, category_id, selector_id, is_pinned, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        uuid,
+                        remote.file.title,
+                        remote.file.content,
+                        category_id,
+                        remote.file.selector_id,
+                        remote.file.is_pinned as i32,
+                        created_at.to_rfc3339(),
+                        updated_at.to_rfc3339()
+                    ],
+                )?;
+                journal.record(ChangeEntity::Note, tx.last_insert_rowid(), None)?;
+            }
+        }
+
+        tx.commit()?;
+        drop(db);
+
+        let canonical = NoteFile {
+            uuid: Some(uuid.clone()),
+            title: remote.file.title.clone(),
+            category_uuid: category_id.and(remote.file.category_uuid.clone()),
+            selector_id: remote.file.selector_id,
+            is_pinned: remote.file.is_pinned,
+            created_at: Some(created_at),
+            updated_at: Some(updated_at),
+            content: remote.file.content.clone(),
+        };
+        self.write_file(&uuid, &canonical, Some(&remote.path), claimed_names)?;
+        Ok(true)
+    }
+
+    /// Returns `false` when the note was edited in the app meanwhile and is kept
+    fn delete_local_note(&self, note: &LocalNote, uuid: &str) -> AppResult<bool> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+
+        let before = journal.snapshot(ChangeEntity::Note, note.id)?;
+        let deleted = tx.execute(
+            "DELETE FROM notes WHERE id = ?1 AND updated_at = ?2",
+            params![note.id, note.updated_at],
+        )?;
+        if deleted == 0 {
+            return Ok(false);
+        }
+        journal.record(ChangeEntity::Note, note.id, before)?;
+        tx.execute("DELETE FROM sync_files WHERE note_uuid = ?1", params![uuid])?;
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn pick_file_name(
+        &self,
+        uuid: &str,
+        title: &str,
+        current_path: Option<&Path>,
+        claimed_names: &HashSet<String>,
+    ) -> String {
+        let base = sanitize_file_name(title);
+        let preferred = format!("{}.md", base);
+        let current = current_path.map(file_name);
+
+        let available = |name: &String| {
+            Some(name) == current.as_ref()
+                || (!claimed_names.contains(name) && !self.root.join(name).exists())
+        };
+
+        if available(&preferred) {
+            preferred
+        } else {
+            format!("{} {}.md", base, &uuid[..8.min(uuid.len())])
+        }
+    }
+
+    fn save_record(&self, uuid: &str, file_name: &str, hash: &str) -> AppResult<()> {
+        self.db.lock().unwrap().connection().execute(
+            "INSERT INTO sync_files (note_uuid, folder, file_name, content_hash, synced_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(note_uuid) DO UPDATE SET folder = excluded.folder, file_name = excluded.file_name,
+                content_hash = excluded.content_hash, synced_at = excluded.synced_at",
+            params![uuid, self.folder_key(), file_name, hash, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn forget_records(&self) -> AppResult<()> {
+        self.db.lock().unwrap().connection().execute(
+            "DELETE FROM sync_files WHERE folder = ?1",
+            params![self.folder_key()],
+        )?;
+        Ok(())
+    }
+
+    fn folder_key(&self) -> String {
+        self.root.to_string_lossy().to_string()
+    }
+
+    fn delete_record(&self, uuid: &str) -> AppResult<()> {
+        self.db.lock().unwrap().connection().execute("DELETE FROM sync_files WHERE note_uuid = ?1", params![uuid])?;
+        Ok(())
+    }
+}
+
+/// Writes through a hidden temp file in the same directory and renames it
+/// over the target, so sync clients and the watcher never see a partial note
+fn write_atomically(path: &Path, text: &str) -> std::io::Result<()> {
+    let temp = path.with_file_name(format!(".{}.tmp", file_name(path)));
+
+    let result = fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(text.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp, path));
+
+    if result.is_err() {
+        fs::remove_file(&temp).ok();
+    }
+    result
+}
+
+/// `Name.md` for the `.Name.md.icloud` placeholder iCloud leaves behind
+/// when it evicts a file
+fn evicted_name(name: &str) -> Option<String> {
+    let original = name.strip_prefix('.')?.strip_suffix(".icloud")?;
+    original.ends_with(".md").then(|| original.to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn sanitize_file_name(title: &str) -> String {
+    let name = title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => ' ',
+            _ => c,
+        })
+        .collect::<String>()
+        .trim()
+        .trim_start_matches('.')
+        .to_string();
+
+    if name.is_empty() {
+        "Untitled".to_string()
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+    use std::time::Duration;
+    use crate::infrastructure::database::{run_migrations, DatabaseConfig};
+
+    /// A scratch directory holding the database and the sync folders
+    struct TestDir(PathBuf);
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn setup() -> (TestDir, Arc<Mutex<Database>>, PathBuf) {
+        let dir = TestDir(std::env::temp_dir().join(format!("extranuts-sync-{}", Uuid::new_v4())));
+        let db = Database::new(DatabaseConfig {
+            path: dir.0.join("notes.db"),
+            device_id: "test".to_string(),
+        }).unwrap();
+        run_migrations(&db).unwrap();
+
+        let folder = dir.0.join("notes");
+        (dir, Arc::new(Mutex::new(db)), folder)
+    }
+
+    fn sync(db: &Arc<Mutex<Database>>, folder: &Path) -> FolderSyncReport {
+        let report = FolderSync::new(db.clone(), folder.to_path_buf()).unwrap().sync().unwrap();
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        report
+    }
+
+    fn add_note(db: &Arc<Mutex<Database>>, title: &str, content: &str) -> i64 {
+        let db = db.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        db.connection().execute(
+            "INSERT INTO notes (uuid, title, content, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?4)",
+            params![Uuid::new_v4().to_string(), title, content, now],
+        ).unwrap();
+        db.connection().last_insert_rowid()
+    }
+
+    fn edit_note(db: &Arc<Mutex<Database>>, id: i64, content: &str) {
+        db.lock().unwrap().connection().execute(
+            "UPDATE notes SET content = ?1, updated_at = ?2 WHERE id = ?3",
+            params![content, Utc::now().to_rfc3339(), id],
+        ).unwrap();
+    }
+
+    fn note_content(db: &Arc<Mutex<Database>>, id: i64) -> Option<String> {
+        db.lock().unwrap().connection().query_row(
+            "SELECT content FROM notes WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).optional().unwrap()
+    }
+
+    fn note_count(db: &Arc<Mutex<Database>>) -> i64 {
+        db.lock().unwrap().connection()
+            .query_row("SELECT COUNT(*) FROM notes", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn edit_file(path: &Path, from: &str, to: &str) {
+        let text = fs::read_to_string(path).unwrap();
+        fs::write(path, text.replace(from, to)).unwrap();
+    }
+
+    fn note_files(folder: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(folder).unwrap()
+            .map(|entry| file_name(&entry.unwrap().path()))
+            .filter(|name| name.ends_with(".md") && !name.starts_with('.'))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn exports_notes_and_imports_new_files() {
+        let (_dir, db, folder) = setup();
+        add_note(&db, "Groceries", "milk\n");
+
+        assert_eq!(sync(&db, &folder).exported, 1);
+        assert_eq!(note_files(&folder), ["Groceries.md"]);
+
+        fs::write(folder.join("Ideas.md"), "dropped in by hand").unwrap();
+        assert_eq!(sync(&db, &folder).imported, 1);
+        assert_eq!(note_count(&db), 2);
+
+        let report = sync(&db, &folder);
+        assert_eq!(report.exported + report.imported + report.updated_local, 0, "{:?}", report);
+    }
+
+    #[test]
+    fn applies_edits_from_either_side() {
+        let (_dir, db, folder) = setup();
+        let id = add_note(&db, "Groceries", "milk\n");
+        sync(&db, &folder);
+
+        edit_file(&folder.join("Groceries.md"), "milk", "eggs");
+        assert_eq!(sync(&db, &folder).updated_local, 1);
+        assert_eq!(note_content(&db, id).as_deref(), Some("eggs\n"));
+
+        edit_note(&db, id, "bread\n");
+        assert_eq!(sync(&db, &folder).exported, 1);
+        assert!(fs::read_to_string(folder.join("Groceries.md")).unwrap().ends_with("bread\n"));
+    }
+
+    #[test]
+    fn conflict_keeps_the_losing_side_as_a_copy() {
+        let (_dir, db, folder) = setup();
+        let id = add_note(&db, "Groceries", "milk\n");
+        sync(&db, &folder);
+
+        edit_note(&db, id, "local\n");
+        sleep(Duration::from_millis(20));
+        edit_file(&folder.join("Groceries.md"), "milk", "remote");
+
+        let report = sync(&db, &folder);
+        assert_eq!((report.conflicts, report.updated_local), (1, 1));
+        assert_eq!(note_content(&db, id).as_deref(), Some("remote\n"));
+        assert_eq!(note_files(&folder), ["Groceries (conflict).md", "Groceries.md"]);
+        assert!(fs::read_to_string(folder.join("Groceries (conflict).md")).unwrap().ends_with("local\n"));
+
+        assert_eq!(sync(&db, &folder).imported, 1);
+        assert_eq!(note_count(&db), 2);
+    }
+
+    #[test]
+    fn deletes_on_either_side() {
+        let (_dir, db, folder) = setup();
+        let first = add_note(&db, "First", "one\n");
+        let second = add_note(&db, "Second", "two\n");
+        sync(&db, &folder);
+
+        fs::remove_file(folder.join("First.md")).unwrap();
+        assert_eq!(sync(&db, &folder).deleted_local, 1);
+        assert_eq!(note_content(&db, first), None);
+
+        db.lock().unwrap().connection()
+            .execute("DELETE FROM notes WHERE id = ?1", params![second])
+            .unwrap();
+        assert_eq!(sync(&db, &folder).deleted_remote, 1);
+        assert!(note_files(&folder).is_empty());
+    }
+
+    #[test]
+    fn empty_or_switched_folder_keeps_notes() {
+        let (dir, db, folder) = setup();
+        let id = add_note(&db, "Groceries", "milk\n");
+        sync(&db, &folder);
+
+        fs::remove_file(folder.join("Groceries.md")).unwrap();
+        let report = sync(&db, &folder);
+        assert_eq!((report.deleted_local, report.exported), (0, 1));
+        assert!(note_content(&db, id).is_some());
+
+        let other = dir.0.join("elsewhere");
+        let report = sync(&db, &other);
+        assert_eq!((report.deleted_local, report.exported), (0, 1));
+        assert_eq!(note_files(&other), ["Groceries.md"]);
+        assert!(note_content(&db, id).is_some());
+    }
+
+    #[test]
+    fn missing_folder_is_reported_not_recreated() {
+        let (_dir, db, folder) = setup();
+        let id = add_note(&db, "Groceries", "milk\n");
+        sync(&db, &folder);
+
+        fs::remove_dir_all(&folder).unwrap();
+        let error = FolderSync::new(db.clone(), folder.clone()).err().unwrap();
+        assert_eq!(error.code, "SYNC_FOLDER_MISSING");
+        assert!(!folder.exists());
+        assert!(note_content(&db, id).is_some());
+    }
+
+    #[test]
+    fn evicted_files_count_as_present() {
+        let (_dir, db, folder) = setup();
+        let id = add_note(&db, "Groceries", "milk\n");
+        sync(&db, &folder);
+
+        fs::rename(folder.join("Groceries.md"), folder.join(".Groceries.md.icloud")).unwrap();
+        let report = sync(&db, &folder);
+        assert_eq!((report.deleted_local, report.exported), (0, 0));
+        assert!(note_content(&db, id).is_some());
+        assert!(note_files(&folder).is_empty());
+    }
+}
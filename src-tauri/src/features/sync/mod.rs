@@ -1,6 +1,10 @@
 pub mod commands;
 pub mod service;
 pub mod models;
+pub mod note_file;
+pub mod folder;
+pub mod watcher;
 
 pub use commands::*;
-pub use models::*;
\ No newline at end of file
+pub use models::*;
+pub use watcher::FolderWatcher;
\ No newline at end of file
@@ -1,13 +1,27 @@
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SyncSettings {
     pub icloud_sync_enabled: bool,
+    pub device_id: String, // Generated on first launch, identifies this Mac in the change journal
+    pub folder_sync_enabled: bool,
+    pub folder_sync_path: Option<String>, // One Markdown file per note, safe for any file-sync service
+    pub last_sync: Option<String>, // RFC 3339, last successful relocation or folder sync
+    pub last_error: Option<String>, // Cleared by the next successful sync
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SyncStatus {
     pub enabled: bool,
-    pub location: String,
+    pub location: String, // "local" | "icloud"
+    pub database_path: String,
+    pub database_size: u64, // bytes
+    pub wal_size: u64, // bytes not yet checkpointed into the database file
+    pub target_directory: String,
+    pub target_writable: bool,
     pub last_sync: Option<String>,
+    pub last_error: Option<String>,
+    pub folder_sync_enabled: bool,
+    pub folder_sync_path: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+/// A note as mirrored in the sync folder: frontmatter followed by the Markdown body
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteFile {
+    pub uuid: Option<String>,
+    pub title: String,
+    pub category_uuid: Option<String>,
+    pub selector_id: Option<i64>,
+    pub is_pinned: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub content: String,
+}
+
+impl NoteFile {
+    /// Canonical file contents. Rendering is deterministic so the hash of the
+    /// output can be compared with the hash recorded at the last sync.
+    pub fn render(&self) -> String {
+        let mut text = String::from("---\n");
+
+        if let Some(ref uuid) = self.uuid {
+            text.push_str(&format!("uuid: {}\n", uuid));
+        }
+        text.push_str(&format!(
+            "title: \"{}\"\n",
+            self.title.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+        if let Some(ref category_uuid) = self.category_uuid {
+            text.push_str(&format!("category: {}\n", category_uuid));
+        }
+        if let Some(selector_id) = self.selector_id {
+            text.push_str(&format!("selector: {}\n", selector_id));
+        }
+        if self.is_pinned {
+            text.push_str("pinned: true\n");
+        }
+        if let Some(created_at) = self.created_at {
+            text.push_str(&format!("created: {}\n", created_at.to_rfc3339()));
+        }
+        if let Some(updated_at) = self.updated_at {
+            text.push_str(&format!("updated: {}\n", updated_at.to_rfc3339()));
+        }
+
+        text.push_str("---\n\n");
+        text.push_str(&self.content);
+        text
+    }
+
+    /// Parses a note file. Files without frontmatter (e.g. dropped in by hand)
+    /// become notes titled after `fallback_title`.
+    pub fn parse(text: &str, fallback_title: &str) -> NoteFile {
+        let mut file = NoteFile {
+            uuid: None,
+            title: fallback_title.to_string(),
+            category_uuid: None,
+            selector_id: None,
+            is_pinned: false,
+            created_at: None,
+            updated_at: None,
+            content: text.to_string(),
+        };
+
+        let text = text.replace("\r\n", "\n");
+        let rest = match text.strip_prefix("---\n") {
+            Some(rest) => rest,
+            None => return file,
+        };
+        let (header, body) = match rest.find("\n---\n") {
+            Some(end) => (&rest[..end], &rest[end + 5..]),
+            None => match rest.strip_suffix("\n---") {
+                Some(header) => (header, ""),
+                None => return file,
+            },
+        };
+
+        for line in header.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            match key {
+                "uuid" if !value.is_empty() => file.uuid = Some(value.to_string()),
+                "title" => file.title = unquote(value),
+                "category" if !value.is_empty() => file.category_uuid = Some(value.to_string()),
+                "selector" => file.selector_id = value.parse().ok(),
+                "pinned" => file.is_pinned = value == "true",
+                "created" => file.created_at = parse_timestamp(value),
+                "updated" => file.updated_at = parse_timestamp(value),
+                _ => {}
+            }
+        }
+
+        file.content = body.strip_prefix('\n').unwrap_or(body).to_string();
+        file
+    }
+}
+
+pub fn content_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    let inner = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner,
+        None => return value.to_string(),
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(escaped) = chars.next() {
+                result.push(escaped);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
@@ -1,5 +1,6 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use chrono::Utc;
 use tauri::AppHandle;
 use crate::core::error::{AppError, AppResult};
 use crate::infrastructure::{
@@ -7,6 +8,8 @@ use crate::infrastructure::{
     storage::{StoragePaths, StorageLocation},
 };
 use crate::features::preferences::PreferencesManager;
+use super::folder::{FolderSync, FolderSyncReport};
+use super::models::SyncStatus;
 
 pub struct SyncService {
     app_handle: AppHandle,
@@ -22,9 +25,101 @@ impl SyncService {
         }
     }
     
-    pub fn get_sync_status(&self) -> AppResult<bool> {
+    pub fn get_sync_status(&self, current_db: Arc<Mutex<Database>>) -> AppResult<SyncStatus> {
         let prefs = self.prefs_manager.load();
-        Ok(prefs.sync.icloud_sync_enabled)
+        
+        let database_path = {
+            let db = current_db.lock().unwrap();
+            db.path().clone()
+        };
+        
+        let location = if prefs.sync.icloud_sync_enabled {
+            StorageLocation::ICloud
+        } else {
+            StorageLocation::Local
+        };
+        let target_directory = StoragePaths::get_database_path(&self.app_handle, location.clone())
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        
+        Ok(SyncStatus {
+            enabled: prefs.sync.icloud_sync_enabled,
+            location: match location {
+                StorageLocation::Local => "local".to_string(),
+                StorageLocation::ICloud => "icloud".to_string(),
+            },
+            database_size: file_size(&database_path),
+            wal_size: file_size(&database_path.with_extension("db-wal")),
+            database_path: database_path.to_string_lossy().to_string(),
+            target_writable: is_writable(&target_directory),
+            target_directory: target_directory.to_string_lossy().to_string(),
+            last_sync: prefs.sync.last_sync,
+            last_error: prefs.sync.last_error,
+            folder_sync_enabled: prefs.sync.folder_sync_enabled,
+            folder_sync_path: prefs.sync.folder_sync_path,
+        })
+    }
+    
+    /// Runs a folder sync and records its outcome for the sync status
+    pub fn run_folder_sync(
+        &self,
+        current_db: Arc<Mutex<Database>>,
+        folder: PathBuf,
+    ) -> AppResult<FolderSyncReport> {
+        let result = FolderSync::new(current_db, folder).and_then(|sync| sync.sync());
+        
+        match result {
+            Ok(ref report) if report.errors.is_empty() => self.record_success(),
+            Ok(ref report) => self.record_error(&report.errors.join("; ")),
+            Err(ref e) => self.record_error(&e.message),
+        }
+        
+        result
+    }
+    
+    /// The sync folder, if folder sync is turned on
+    pub fn folder_sync_path(&self) -> Option<PathBuf> {
+        let prefs = self.prefs_manager.load();
+        if !prefs.sync.folder_sync_enabled {
+            return None;
+        }
+        prefs.sync.folder_sync_path.map(PathBuf::from)
+    }
+    
+    pub fn configure_folder_sync(
+        &self,
+        enabled: bool,
+        path: Option<String>,
+    ) -> AppResult<Option<PathBuf>> {
+        let mut prefs = self.prefs_manager.load();
+        
+        if let Some(path) = path.filter(|p| !p.trim().is_empty()) {
+            prefs.sync.folder_sync_path = Some(path);
+        }
+        
+        if enabled {
+            let folder = prefs.sync.folder_sync_path.as_ref()
+                .ok_or_else(|| AppError::new("SYNC_FOLDER_MISSING", "Choose a sync folder first"))?;
+            
+            // Keep the live database out of the folder we mirror notes into
+            let db_location = if prefs.sync.icloud_sync_enabled {
+                StorageLocation::ICloud
+            } else {
+                StorageLocation::Local
+            };
+            if StoragePaths::get_database_path(&self.app_handle, db_location).starts_with(folder) {
+                return Err(AppError::new(
+                    "SYNC_FOLDER_CONFLICT",
+                    "The sync folder cannot contain the database",
+                ));
+            }
+        }
+        
+        prefs.sync.folder_sync_enabled = enabled;
+        self.prefs_manager.save(&prefs)?;
+        
+        Ok(self.folder_sync_path())
     }
     
     pub fn toggle_icloud_sync(
@@ -32,15 +127,37 @@ impl SyncService {
         enabled: bool,
         current_db: Arc<Mutex<Database>>,
     ) -> AppResult<(String, Database)> {
-        let mut prefs = self.prefs_manager.load();
+        let prefs = self.prefs_manager.load();
         
         if prefs.sync.icloud_sync_enabled == enabled {
             return Err(AppError::new("NO_CHANGE", "Sync setting already at requested state"));
         }
         
-        // Update preferences
-        prefs.sync.icloud_sync_enabled = enabled;
-        self.prefs_manager.save(&prefs)?;
+        match self.relocate_database(enabled, current_db) {
+            Ok(new_db) => {
+                // Only persist the new location once the database is there
+                let mut prefs = self.prefs_manager.load();
+                prefs.sync.icloud_sync_enabled = enabled;
+                prefs.sync.last_sync = Some(Utc::now().to_rfc3339());
+                prefs.sync.last_error = None;
+                self.prefs_manager.save(&prefs)?;
+                
+                let message = format!("iCloud sync {}", if enabled { "enabled" } else { "disabled" });
+                Ok((message, new_db))
+            }
+            Err(e) => {
+                self.record_error(&e.message);
+                Err(e)
+            }
+        }
+    }
+    
+    fn relocate_database(
+        &self,
+        enabled: bool,
+        current_db: Arc<Mutex<Database>>,
+    ) -> AppResult<Database> {
+        let prefs = self.prefs_manager.load();
         
         // Get paths
         let old_path = {
@@ -62,11 +179,26 @@ impl SyncService {
         }
         
         // Create new database connection
-        let new_config = DatabaseConfig { path: new_path };
+        let new_config = DatabaseConfig {
+            path: new_path,
+            device_id: prefs.sync.device_id.clone(),
+        };
         let new_db = Database::new(new_config)?;
         
-        let message = format!("iCloud sync {}", if enabled { "enabled" } else { "disabled" });
-        Ok((message, new_db))
+        Ok(new_db)
+    }
+    
+    fn record_success(&self) {
+        let mut prefs = self.prefs_manager.load();
+        prefs.sync.last_sync = Some(Utc::now().to_rfc3339());
+        prefs.sync.last_error = None;
+        let _ = self.prefs_manager.save(&prefs);
+    }
+    
+    fn record_error(&self, message: &str) {
+        let mut prefs = self.prefs_manager.load();
+        prefs.sync.last_error = Some(message.to_string());
+        let _ = self.prefs_manager.save(&prefs);
     }
     
     fn migrate_database(&self, from: &PathBuf, to: &PathBuf) -> AppResult<()> {
@@ -99,4 +231,21 @@ impl SyncService {
         
         Ok(())
     }
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Probes the directory (or its nearest existing ancestor) with a throwaway file
+fn is_writable(dir: &Path) -> bool {
+    let existing = match dir.ancestors().find(|p| p.exists()) {
+        Some(existing) => existing,
+        None => return false,
+    };
+    
+    let probe = existing.join(".extranuts-write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
 }
\ No newline at end of file
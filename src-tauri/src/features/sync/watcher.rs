@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::time::Duration;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::core::AppState;
+use crate::core::error::{AppError, AppResult};
+use crate::features::windows::tray::refresh_tray;
+use super::service::SyncService;
+
+// Editors save through temp files and renames; wait for the burst to settle
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// What woke the sync thread: a change in the folder, or notes edited in the app
+enum Signal {
+    Folder(notify::Result<Event>),
+    Local,
+}
+
+impl Signal {
+    fn is_relevant(&self) -> bool {
+        match self {
+            Signal::Folder(event) => is_note_event(event),
+            Signal::Local => true,
+        }
+    }
+}
+
+/// Keeps the sync folder watcher alive. Managed as Tauri state.
+#[derive(Default)]
+pub struct FolderWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    local: Mutex<Option<Sender<Signal>>>,
+}
+
+impl FolderWatcher {
+    pub fn start(&self, app: &AppHandle, root: PathBuf) -> AppResult<()> {
+        let (tx, rx) = channel::<Signal>();
+        let local = tx.clone();
+        
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(Signal::Folder(event));
+        }).map_err(|e| AppError::new("SYNC_WATCH_ERROR", e.to_string()))?;
+        
+        watcher.watch(&root, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::new("SYNC_WATCH_ERROR", e.to_string()))?;
+        
+        let app = app.clone();
+        std::thread::spawn(move || {
+            // The channel closes when `stop` drops both senders, which ends the thread
+            while let Ok(signal) = rx.recv() {
+                let mut relevant = signal.is_relevant();
+                loop {
+                    match rx.recv_timeout(DEBOUNCE) {
+                        Ok(signal) => relevant |= signal.is_relevant(),
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                
+                if relevant {
+                    run_sync(&app, root.clone());
+                }
+            }
+        });
+        
+        *self.watcher.lock().unwrap() = Some(watcher);
+        *self.local.lock().unwrap() = Some(local);
+        Ok(())
+    }
+    
+    pub fn stop(&self) {
+        self.watcher.lock().unwrap().take();
+        self.local.lock().unwrap().take();
+    }
+    
+    /// Queues a sync for notes changed in the app, run once the burst of
+    /// changes settles. Does nothing while folder sync is off.
+    pub fn request_sync(&self) {
+        if let Some(local) = self.local.lock().unwrap().as_ref() {
+            let _ = local.send(Signal::Local);
+        }
+    }
+}
+
+/// Mirrors a note mutation into the sync folder, debounced with folder events
+pub fn sync_after_change(app: &AppHandle) {
+    if let Some(watcher) = app.try_state::<FolderWatcher>() {
+        watcher.request_sync();
+    }
+}
+
+/// Syncs once and tells the frontend what changed
+pub fn run_sync(app: &AppHandle, root: PathBuf) {
+    let db = app.state::<AppState>().db();
+    let service = SyncService::new(app.clone());
+    
+    match service.run_folder_sync(db, root) {
+        Ok(report) => {
+            let _ = app.emit("folder-sync-completed", &report);
+        }
+        Err(e) => eprintln!("Folder sync failed: {}", e.message),
+    }
+    
+    // Imported notes change the counts, and failures the sync status
+    refresh_tray(app);
+}
+
+fn is_note_event(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+        }
+        Err(_) => false,
+    }
+}
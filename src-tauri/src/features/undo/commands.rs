@@ -0,0 +1,40 @@
+use tauri::{AppHandle, State};
+use crate::core::AppState;
+use super::models::{UndoResult, UndoState};
+use super::service::UndoService;
+use crate::features::sync::watcher::sync_after_change;
+use crate::features::windows::tray::refresh_tray;
+
+#[tauri::command]
+pub fn undo_last(
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Option<UndoResult>, String> {
+    let service = UndoService::new(state.db(), state.undo_history());
+    let result = service.undo_last()
+        .map_err(|e| e.message)?;
+    refresh_tray(&app);
+    sync_after_change(&app);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn redo(
+    app: AppHandle,
+    state: State<AppState>,
+) -> Result<Option<UndoResult>, String> {
+    let service = UndoService::new(state.db(), state.undo_history());
+    let result = service.redo()
+        .map_err(|e| e.message)?;
+    refresh_tray(&app);
+    sync_after_change(&app);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_undo_state(
+    state: State<AppState>,
+) -> Result<UndoState, String> {
+    let service = UndoService::new(state.db(), state.undo_history());
+    Ok(service.state())
+}
@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+use super::models::{UndoEntry, UndoState};
+
+const MAX_UNDO_HISTORY: usize = 50;
+
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+}
+
+impl UndoHistory {
+    /// Records a new operation; anything that could be redone is discarded
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.redo.clear();
+        self.push_undo(entry);
+    }
+    
+    pub fn pop_undo(&mut self) -> Option<UndoEntry> {
+        self.undo.pop_back()
+    }
+    
+    pub fn pop_redo(&mut self) -> Option<UndoEntry> {
+        self.redo.pop()
+    }
+    
+    pub fn push_undo(&mut self, entry: UndoEntry) {
+        self.undo.push_back(entry);
+        if self.undo.len() > MAX_UNDO_HISTORY {
+            self.undo.pop_front();
+        }
+    }
+    
+    pub fn push_redo(&mut self, entry: UndoEntry) {
+        self.redo.push(entry);
+    }
+    
+    pub fn state(&self) -> UndoState {
+        UndoState {
+            undo_label: self.undo.back().map(|e| e.label.clone()),
+            redo_label: self.redo.last().map(|e| e.label.clone()),
+        }
+    }
+}
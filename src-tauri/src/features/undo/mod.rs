@@ -0,0 +1,8 @@
+pub mod models;
+pub mod history;
+pub mod service;
+pub mod commands;
+
+pub use history::UndoHistory;
+pub use service::UndoService;
+pub use commands::*;
@@ -0,0 +1,22 @@
+use serde::Serialize;
+
+/// A user-level operation, identified by the change journal entries it produced
+#[derive(Debug, Clone, Serialize)]
+pub struct UndoEntry {
+    pub label: String,
+    pub change_ids: Vec<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UndoState {
+    pub undo_label: Option<String>,
+    pub redo_label: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UndoResult {
+    pub label: String,
+    pub changes_applied: usize,
+    pub conflicts: Vec<String>, // Rows edited since the operation, left as they are
+    pub state: UndoState,
+}
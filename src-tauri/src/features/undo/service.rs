@@ -0,0 +1,167 @@
+use std::sync::{Arc, Mutex};
+use crate::core::error::{AppError, AppResult};
+use crate::infrastructure::database::Database;
+use crate::features::journal::{ChangeEntity, ChangeJournal};
+use crate::features::journal::recorder::RESTORE_CONFLICT;
+use crate::features::journal::models::ChangeEntry;
+use crate::features::journal::repository::JournalRepository;
+use super::history::UndoHistory;
+use super::models::{UndoEntry, UndoResult, UndoState};
+
+pub struct UndoService {
+    db: Arc<Mutex<Database>>,
+    history: Arc<Mutex<UndoHistory>>,
+    journal: JournalRepository,
+}
+
+impl UndoService {
+    pub fn new(db: Arc<Mutex<Database>>, history: Arc<Mutex<UndoHistory>>) -> Self {
+        Self {
+            journal: JournalRepository::new(Arc::clone(&db)),
+            db,
+            history,
+        }
+    }
+    
+    /// Runs `operation` and makes the journal entries it wrote undoable as one step
+    pub fn record<T>(&self, label: &str, operation: impl FnOnce() -> AppResult<T>) -> AppResult<T> {
+        let (change_ids, result) = ChangeJournal::capture(operation);
+        let result = result?;
+        
+        if !change_ids.is_empty() {
+            self.history.lock().unwrap().push(UndoEntry {
+                label: label.to_string(),
+                change_ids,
+            });
+        }
+        
+        Ok(result)
+    }
+    
+    pub fn undo_last(&self) -> AppResult<Option<UndoResult>> {
+        let entry = match self.history.lock().unwrap().pop_undo() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        
+        let mut changes = match self.journal.get_changes(&entry.change_ids) {
+            Ok(changes) => changes,
+            Err(e) => {
+                self.history.lock().unwrap().push_undo(entry);
+                return Err(e);
+            }
+        };
+        changes.reverse();
+        
+        let outcome = self.apply(&changes, |change| change.after.as_ref(), |change| change.before.as_ref());
+        let (changes_applied, conflicts) = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                let message = format!("Could not undo \"{}\": {}", entry.label, e.message);
+                self.history.lock().unwrap().push_undo(entry);
+                return Err(AppError::new("UNDO_FAILED", message));
+            }
+        };
+        
+        let mut history = self.history.lock().unwrap();
+        let label = entry.label.clone();
+        history.push_redo(entry);
+        
+        Ok(Some(UndoResult {
+            label,
+            changes_applied,
+            conflicts,
+            state: history.state(),
+        }))
+    }
+    
+    pub fn redo(&self) -> AppResult<Option<UndoResult>> {
+        let entry = match self.history.lock().unwrap().pop_redo() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        
+        let changes = match self.journal.get_changes(&entry.change_ids) {
+            Ok(changes) => changes,
+            Err(e) => {
+                self.history.lock().unwrap().push_redo(entry);
+                return Err(e);
+            }
+        };
+        
+        let outcome = self.apply(&changes, |change| change.before.as_ref(), |change| change.after.as_ref());
+        let (changes_applied, conflicts) = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                let message = format!("Could not redo \"{}\": {}", entry.label, e.message);
+                self.history.lock().unwrap().push_redo(entry);
+                return Err(AppError::new("REDO_FAILED", message));
+            }
+        };
+        
+        let mut history = self.history.lock().unwrap();
+        let label = entry.label.clone();
+        history.push_undo(entry);
+        
+        Ok(Some(UndoResult {
+            label,
+            changes_applied,
+            conflicts,
+            state: history.state(),
+        }))
+    }
+    
+    pub fn state(&self) -> UndoState {
+        self.history.lock().unwrap().state()
+    }
+    
+    /// Restores every change to the image picked by `image`, all or nothing.
+    /// A row that no longer matches `expected` was edited since the operation;
+    /// it is left alone, along with its earlier changes, and reported instead
+    /// of overwriting that edit. So is a row that would clash with another one,
+    /// like a category whose name was reused. Returns the number of rows
+    /// restored and the conflicting ones.
+    fn apply(
+        &self,
+        changes: &[ChangeEntry],
+        expected: impl Fn(&ChangeEntry) -> Option<&serde_json::Value>,
+        image: impl Fn(&ChangeEntry) -> Option<&serde_json::Value>,
+    ) -> AppResult<(usize, Vec<String>)> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let tx = conn.unchecked_transaction()
+            .map_err(|e| AppError::new("TRANSACTION_ERROR", e.to_string()))?;
+        let journal = ChangeJournal::new(&tx, db.device_id());
+        
+        let mut applied = 0;
+        let mut conflicts: Vec<(ChangeEntity, i64)> = Vec::new();
+        
+        for change in changes {
+            let row = (change.entity, change.entity_id);
+            if conflicts.contains(&row)
+                || journal.matches(change.entity, change.entity_id, image(change))?
+            {
+                continue;
+            }
+            
+            if !journal.matches(change.entity, change.entity_id, expected(change))? {
+                conflicts.push(row);
+                continue;
+            }
+            
+            match journal.restore(change.entity, change.entity_id, image(change)) {
+                Ok(()) => applied += 1,
+                Err(e) if e.code == RESTORE_CONFLICT => conflicts.push(row),
+                Err(e) => return Err(e),
+            }
+        }
+        
+        tx.commit()?;
+        
+        let conflicts = conflicts.into_iter()
+            .map(|(entity, id)| format!("{} {}", entity.as_str(), id))
+            .collect();
+        Ok((applied, conflicts))
+    }
+}
@@ -1,12 +1,14 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, WebviewWindow};
+use crate::features::notes::models::Note;
+use super::models::{FloatingLayout, FloatingWindowInfo};
 use super::service::WindowService;
 
 #[tauri::command]
 pub fn create_floating_window(
     app: AppHandle,
     label: String,
-    width: f64,
-    height: f64,
+    width: Option<f64>,
+    height: Option<f64>,
 ) -> Result<(), String> {
     WindowService::create_floating_window(&app, label, width, height)
         .map_err(|e| e.message)
@@ -28,9 +30,64 @@ pub fn show_in_menu_bar(app: AppHandle) -> Result<(), String> {
 pub fn create_floating_note(
     app: AppHandle,
     note_id: i64,
-    title: String,
-    content: String,
 ) -> Result<(), String> {
-    WindowService::create_floating_note(&app, note_id, title, content)
+    WindowService::create_floating_note(&app, note_id)
         .map_err(|e| e.message)
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub fn get_floating_note(
+    app: AppHandle,
+    window: WebviewWindow,
+) -> Result<Note, String> {
+    WindowService::get_floating_note(&app, window.label())
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn list_floating_notes(app: AppHandle) -> Result<Vec<FloatingWindowInfo>, String> {
+    WindowService::list_floating_notes(&app)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn focus_floating_note(app: AppHandle, note_id: i64) -> Result<(), String> {
+    WindowService::focus_floating_note(&app, note_id)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn close_all_floating_notes(app: AppHandle) -> Result<usize, String> {
+    WindowService::close_all_floating_notes(&app)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn arrange_floating_notes(app: AppHandle, layout: FloatingLayout) -> Result<(), String> {
+    WindowService::arrange_floating_notes(&app, layout)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn toggle_all_always_on_top(app: AppHandle) -> Result<bool, String> {
+    WindowService::toggle_all_always_on_top(&app)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn get_window_opacity(app: AppHandle, window: WebviewWindow) -> Result<f64, String> {
+    WindowService::get_window_opacity(&app, window.label())
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn set_window_opacity(app: AppHandle, label: String, value: Option<f64>) -> Result<f64, String> {
+    WindowService::set_window_opacity(&app, &label, value)
+        .map_err(|e| e.message)
+}
+
+#[tauri::command]
+pub fn open_quick_capture(app: AppHandle) -> Result<(), String> {
+    WindowService::open_quick_capture(&app)
+        .map_err(|e| e.message)
+}
@@ -1,4 +1,7 @@
 pub mod commands;
+pub mod models;
+pub mod registry;
+pub mod repository;
 pub mod service;
 pub mod tray;
 
@@ -0,0 +1,48 @@
+use serde::{Serialize, Deserialize};
+
+/// Saved state of a floating note window, in logical pixels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FloatingWindowState {
+    pub note_id: i64,
+    pub x: Option<f64>, // None until the window has been moved
+    pub y: Option<f64>,
+    pub width: f64,
+    pub height: f64,
+    pub always_on_top: bool,
+    pub opacity: Option<f64>, // Overrides the transparency preference when set
+    pub monitor: Option<String>, // Name of the monitor the window was last on
+    pub is_open: bool, // Still open when the app quit, so reopened on launch
+}
+
+impl FloatingWindowState {
+    pub fn new(note_id: i64, width: f64, height: f64) -> Self {
+        Self {
+            note_id,
+            x: None,
+            y: None,
+            width,
+            height,
+            always_on_top: true,
+            opacity: None,
+            monitor: None,
+            is_open: true,
+        }
+    }
+}
+
+/// An open floating note window as listed by the registry
+#[derive(Debug, Clone, Serialize)]
+pub struct FloatingWindowInfo {
+    pub label: String,
+    pub note_id: i64,
+    pub title: String,
+    pub always_on_top: bool,
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatingLayout {
+    Cascade, // Stacked diagonally, keeping each window's size
+    Tile,    // Resized into a grid filling the monitor
+}
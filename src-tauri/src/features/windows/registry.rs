@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Floating note windows currently open, keyed by note id, so a note is
+/// never shown in two windows at once
+#[derive(Default)]
+pub struct FloatingWindowRegistry {
+    windows: Mutex<HashMap<i64, String>>,
+}
+
+impl FloatingWindowRegistry {
+    pub fn label_for(note_id: i64) -> String {
+        format!("floating_note_{}", note_id)
+    }
+
+    pub fn get(&self, note_id: i64) -> Option<String> {
+        self.windows.lock().unwrap().get(&note_id).cloned()
+    }
+
+    pub fn note_for_label(&self, label: &str) -> Option<i64> {
+        self.windows.lock().unwrap()
+            .iter()
+            .find(|(_, window_label)| window_label.as_str() == label)
+            .map(|(note_id, _)| *note_id)
+    }
+
+    /// All open windows as (note id, label), ordered by note id
+    pub fn entries(&self) -> Vec<(i64, String)> {
+        let mut entries: Vec<(i64, String)> = self.windows.lock().unwrap()
+            .iter()
+            .map(|(note_id, label)| (*note_id, label.clone()))
+            .collect();
+        entries.sort_by_key(|(note_id, _)| *note_id);
+        entries
+    }
+
+    pub fn register(&self, note_id: i64, label: String) {
+        self.windows.lock().unwrap().insert(note_id, label);
+    }
+
+    pub fn unregister(&self, note_id: i64) {
+        self.windows.lock().unwrap().remove(&note_id);
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+use rusqlite::{params, OptionalExtension, Row};
+use chrono::Utc;
+use crate::core::error::AppResult;
+use crate::infrastructure::database::Database;
+use super::models::FloatingWindowState;
+
+/// Floating window geometry is specific to this machine's screens, so unlike
+/// notes it isn't recorded in the change journal.
+pub struct FloatingWindowRepository {
+    db: Arc<Mutex<Database>>,
+}
+
+impl FloatingWindowRepository {
+    pub fn new(db: Arc<Mutex<Database>>) -> Self {
+        Self { db }
+    }
+
+    pub fn get_state(&self, note_id: i64) -> AppResult<Option<FloatingWindowState>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let state = conn.query_row(
+            "SELECT note_id, x, y, width, height, always_on_top, opacity, monitor, is_open
+             FROM floating_windows WHERE note_id = ?1",
+            params![note_id],
+            Self::map_row
+        ).optional()?;
+        
+        Ok(state)
+    }
+
+    /// Windows that were still open when the app last quit
+    pub fn get_open_windows(&self) -> AppResult<Vec<FloatingWindowState>> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        let mut stmt = conn.prepare(
+            "SELECT note_id, x, y, width, height, always_on_top, opacity, monitor, is_open
+             FROM floating_windows WHERE is_open = 1 ORDER BY updated_at ASC"
+        )?;
+        
+        let states = stmt.query_map([], Self::map_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(states)
+    }
+
+    pub fn save_state(&self, state: &FloatingWindowState) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "INSERT INTO floating_windows (note_id, x, y, width, height, always_on_top, opacity, monitor, is_open, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(note_id) DO UPDATE SET
+                x = excluded.x, y = excluded.y, width = excluded.width, height = excluded.height,
+                always_on_top = excluded.always_on_top, opacity = excluded.opacity,
+                monitor = excluded.monitor, is_open = excluded.is_open, updated_at = excluded.updated_at",
+            params![
+                state.note_id,
+                state.x,
+                state.y,
+                state.width,
+                state.height,
+                state.always_on_top,
+                state.opacity,
+                state.monitor,
+                state.is_open,
+                Utc::now().to_rfc3339()
+            ],
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn save_position(&self, note_id: i64, x: f64, y: f64, monitor: Option<String>) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "UPDATE floating_windows SET x = ?1, y = ?2, monitor = COALESCE(?3, monitor), updated_at = ?4
+             WHERE note_id = ?5",
+            params![x, y, monitor, Utc::now().to_rfc3339(), note_id],
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn save_size(&self, note_id: i64, width: f64, height: f64) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "UPDATE floating_windows SET width = ?1, height = ?2, updated_at = ?3 WHERE note_id = ?4",
+            params![width, height, Utc::now().to_rfc3339(), note_id],
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn save_always_on_top(&self, note_id: i64, always_on_top: bool) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "UPDATE floating_windows SET always_on_top = ?1, updated_at = ?2 WHERE note_id = ?3",
+            params![always_on_top, Utc::now().to_rfc3339(), note_id],
+        )?;
+        
+        Ok(())
+    }
+
+    /// `None` drops the note's override so it follows the preference again
+    pub fn save_opacity(&self, note_id: i64, opacity: Option<f64>) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "UPDATE floating_windows SET opacity = ?1, updated_at = ?2 WHERE note_id = ?3",
+            params![opacity, Utc::now().to_rfc3339(), note_id],
+        )?;
+        
+        Ok(())
+    }
+
+    pub fn set_open(&self, note_id: i64, is_open: bool) -> AppResult<()> {
+        let db = self.db.lock().unwrap();
+        let conn = db.connection();
+        
+        conn.execute(
+            "UPDATE floating_windows SET is_open = ?1 WHERE note_id = ?2",
+            params![is_open, note_id],
+        )?;
+        
+        Ok(())
+    }
+
+    fn map_row(row: &Row) -> rusqlite::Result<FloatingWindowState> {
+        Ok(FloatingWindowState {
+            note_id: row.get(0)?,
+            x: row.get(1)?,
+            y: row.get(2)?,
+            width: row.get(3)?,
+            height: row.get(4)?,
+            always_on_top: row.get(5)?,
+            opacity: row.get(6)?,
+            monitor: row.get(7)?,
+            is_open: row.get(8)?,
+        })
+    }
+}
@@ -1,5 +1,19 @@
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tauri::{AppHandle, Emitter, LogicalPosition, LogicalSize, Manager, Monitor, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+use crate::core::AppState;
 use crate::core::error::{AppError, AppResult};
+use crate::features::notes::models::Note;
+use crate::features::notes::service::NoteService;
+use crate::features::preferences::{models::{clamp_opacity, WindowPreferences}, PreferencesManager};
+use super::models::{FloatingLayout, FloatingWindowInfo, FloatingWindowState};
+use super::registry::FloatingWindowRegistry;
+use super::repository::FloatingWindowRepository;
+use super::tray::refresh_tray;
+
+/// Emitted to a window with its new opacity; the webview is transparent and
+/// applies the value itself
+pub const OPACITY_CHANGED_EVENT: &str = "window-opacity-changed";
+
+pub const QUICK_CAPTURE_LABEL: &str = "quick_capture";
 
 pub struct WindowService;
 
@@ -7,16 +21,19 @@ impl WindowService {
     pub fn create_floating_window(
         app: &AppHandle,
         label: String,
-        width: f64,
-        height: f64,
+        width: Option<f64>,
+        height: Option<f64>,
     ) -> AppResult<()> {
+        let prefs = Self::window_preferences(app);
+        
         WebviewWindowBuilder::new(app, label.clone(), WebviewUrl::App("index.html".into()))
             .title("Extranuts Note")
-            .inner_size(width, height)
+            .inner_size(width.unwrap_or(prefs.default_width), height.unwrap_or(prefs.default_height))
             .resizable(true)
             .always_on_top(true)
             .decorations(true)
             .skip_taskbar(true)
+            .transparent(true)
             .build()
             .map_err(|e| AppError::new("WINDOW_CREATE_ERROR", e.to_string()))?;
         
@@ -30,6 +47,13 @@ impl WindowService {
         window.set_always_on_top(!current)
             .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
         
+        // Floating notes keep the setting for the next time they open
+        let app = window.app_handle();
+        if let Some(note_id) = app.state::<FloatingWindowRegistry>().note_for_label(window.label()) {
+            FloatingWindowRepository::new(app.state::<AppState>().db())
+                .save_always_on_top(note_id, !current)?;
+        }
+        
         Ok(())
     }
     
@@ -43,44 +67,433 @@ impl WindowService {
         if let Some(window) = app.get_webview_window("main") {
             let _ = window.hide();
         }
+        refresh_tray(app);
         
         Ok(())
     }
     
-    pub fn create_floating_note(
-        app: &AppHandle,
-        note_id: i64,
-        title: String,
-        _content: String,
-    ) -> AppResult<()> {
-        // Create unique window label with note ID
-        let window_label = format!("floating_note_{}", note_id);
+    pub fn show_main_window(app: &AppHandle) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        refresh_tray(app);
+    }
+    
+    pub fn toggle_main_window(app: &AppHandle) {
+        if let Some(window) = app.get_webview_window("main") {
+            if window.is_visible().unwrap_or(false) {
+                let _ = window.hide();
+            } else {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        refresh_tray(app);
+    }
+    
+    /// Brings the main window forward and hands it an action to perform,
+    /// for the tray menu and global shortcuts
+    pub fn request_from_main(app: &AppHandle, event: &str) -> AppResult<()> {
+        Self::show_main_window(app);
+        app.emit_to("main", event, ())
+            .map_err(|e| AppError::new("WINDOW_EVENT_ERROR", e.to_string()))
+    }
+    
+    /// Opens the one-line capture window, or brings it back if it's open
+    pub fn open_quick_capture(app: &AppHandle) -> AppResult<()> {
+        if let Some(window) = app.get_webview_window(QUICK_CAPTURE_LABEL) {
+            return Self::focus_window(&window);
+        }
+        
+        WebviewWindowBuilder::new(
+            app,
+            QUICK_CAPTURE_LABEL,
+            WebviewUrl::App("index.html?quickCapture=true".into())
+        )
+            .title("Quick Capture")
+            .inner_size(560.0, 64.0)
+            .resizable(false)
+            .always_on_top(true)
+            .decorations(false)
+            .skip_taskbar(true)
+            .transparent(true)
+            .center()
+            .focused(true)
+            .build()
+            .map_err(|e| AppError::new("WINDOW_CREATE_ERROR", e.to_string()))?;
         
-        // Create the floating window with URL parameters (simple encoding)
-        let url = format!("index.html?floating=true&noteId={}&title={}&content={}", 
-            note_id, 
-            title.replace(" ", "%20").replace("&", "%26"), 
-            "test_content" // Simplified for now
-        );
+        Ok(())
+    }
+    
+    /// Opens a floating window for the note, or focuses it if one is already
+    /// open. The window only gets the note id and loads the note itself.
+    pub fn create_floating_note(app: &AppHandle, note_id: i64) -> AppResult<()> {
+        let registry = app.state::<FloatingWindowRegistry>();
+        if let Some(window) = registry.get(note_id).and_then(|label| app.get_webview_window(&label)) {
+            return Self::focus_window(&window);
+        }
         
-        let _window = WebviewWindowBuilder::new(
+        let note = NoteService::new(app.state::<AppState>().db())
+            .get_note(note_id)?
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("Note with id {} not found", note_id)))?;
+        
+        let window_label = FloatingWindowRegistry::label_for(note_id);
+        
+        // Reopen where the note's window was last left
+        let repository = FloatingWindowRepository::new(app.state::<AppState>().db());
+        let mut state = repository.get_state(note_id)?
+            .unwrap_or_else(|| {
+                let prefs = Self::window_preferences(app);
+                FloatingWindowState::new(note_id, prefs.default_width, prefs.default_height)
+            });
+        state.is_open = true;
+        
+        let url = format!("index.html?floating=true&noteId={}", note_id);
+        
+        let mut builder = WebviewWindowBuilder::new(
             app, 
-            window_label, 
+            window_label.clone(), 
             WebviewUrl::App(url.into())
         )
-            .title(&format!("Note: {}", title))
-            .inner_size(400.0, 300.0)
+            .title(format!("Note: {}", note.title))
+            .inner_size(state.width, state.height)
             .min_inner_size(300.0, 200.0)
             .resizable(true)
-            .always_on_top(true)
+            .always_on_top(state.always_on_top)
             .decorations(false)  // Remove window decorations for ScreenFloat-like appearance
             .skip_taskbar(true)
-            .transparent(true)   // Enable transparency for glassmorphism effect
-            .build()
+            .transparent(true);  // Enable transparency for glassmorphism effect
+        
+        // A saved position on a monitor that's no longer attached would put
+        // the window off screen, so fall back to the default placement
+        if let (Some(x), Some(y)) = (state.x, state.y) {
+            if Self::monitor_available(app, state.monitor.as_deref()) {
+                builder = builder.position(x, y);
+            }
+        }
+        
+        let window = builder.build()
             .map_err(|e| AppError::new("FLOATING_NOTE_CREATE_ERROR", e.to_string()))?;
         
-        // Window will read data from URL parameters
+        // Only marked open once the window exists, so a failed build isn't
+        // retried on every launch
+        if let Err(e) = repository.save_state(&state) {
+            let _ = window.destroy();
+            return Err(e);
+        }
+        
+        registry.register(note_id, window_label);
+        Self::track_floating_note(app, &window, note_id);
+        
+        Ok(())
+    }
+    
+    /// The note shown by a floating window, resolved from the window's label
+    /// so the view doesn't need the id passed in. This is not an access check:
+    /// app commands aren't restricted per window, so a floating window can
+    /// still call the other note commands.
+    pub fn get_floating_note(app: &AppHandle, label: &str) -> AppResult<Note> {
+        let note_id = app.state::<FloatingWindowRegistry>()
+            .note_for_label(label)
+            .ok_or_else(|| AppError::new("NOT_FLOATING_WINDOW", format!("Window '{}' is not a floating note", label)))?;
+        
+        NoteService::new(app.state::<AppState>().db())
+            .get_note(note_id)?
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("Note with id {} not found", note_id)))
+    }
+    
+    pub fn list_floating_notes(app: &AppHandle) -> AppResult<Vec<FloatingWindowInfo>> {
+        let notes = NoteService::new(app.state::<AppState>().db());
+        let mut windows = Vec::new();
+        
+        for (note_id, window) in Self::floating_windows(app) {
+            let title = notes.get_note(note_id)?
+                .map(|note| note.title)
+                .unwrap_or_default();
+            
+            windows.push(FloatingWindowInfo {
+                label: window.label().to_string(),
+                note_id,
+                title,
+                always_on_top: window.is_always_on_top().unwrap_or(false),
+                focused: window.is_focused().unwrap_or(false),
+            });
+        }
+        
+        Ok(windows)
+    }
+    
+    pub fn focus_floating_note(app: &AppHandle, note_id: i64) -> AppResult<()> {
+        let window = app.state::<FloatingWindowRegistry>()
+            .get(note_id)
+            .and_then(|label| app.get_webview_window(&label))
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("No floating window open for note {}", note_id)))?;
+        
+        Self::focus_window(&window)
+    }
+    
+    /// Closes every floating note. Closing goes through the normal close
+    /// request, so these windows aren't reopened on the next launch.
+    pub fn close_all_floating_notes(app: &AppHandle) -> AppResult<usize> {
+        let windows = Self::floating_windows(app);
+        
+        for (_, window) in &windows {
+            window.close()
+                .map_err(|e| AppError::new("WINDOW_CLOSE_ERROR", e.to_string()))?;
+        }
+        
+        Ok(windows.len())
+    }
+    
+    /// Arranges the floating notes on the monitor under the cursor. The new
+    /// geometry is saved through the usual move and resize events.
+    pub fn arrange_floating_notes(app: &AppHandle, layout: FloatingLayout) -> AppResult<()> {
+        let windows = Self::floating_windows(app);
+        if windows.is_empty() {
+            return Ok(());
+        }
+        
+        let monitor = Self::current_monitor(app)?;
+        let scale = monitor.scale_factor();
+        let origin = monitor.position().to_logical::<f64>(scale);
+        let area = monitor.size().to_logical::<f64>(scale);
+        
+        // Keep clear of the macOS menu bar and the screen edges
+        const MARGIN: f64 = 16.0;
+        const MENU_BAR: f64 = 32.0;
+        let left = origin.x + MARGIN;
+        let top = origin.y + MENU_BAR + MARGIN;
+        let width = area.width - 2.0 * MARGIN;
+        let height = area.height - MENU_BAR - 2.0 * MARGIN;
+        
+        let update = |window: &WebviewWindow, x: f64, y: f64, size: Option<(f64, f64)>| -> AppResult<()> {
+            if let Some((w, h)) = size {
+                window.set_size(LogicalSize::new(w, h))
+                    .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+            }
+            window.set_position(LogicalPosition::new(x, y))
+                .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))
+        };
+        
+        match layout {
+            FloatingLayout::Cascade => {
+                const STEP: f64 = 32.0;
+                let (mut x, mut y) = (left, top);
+                let mut column_start = left;
+                
+                for (_, window) in &windows {
+                    let size = window.outer_size()
+                        .map(|size| size.to_logical::<f64>(scale))
+                        .map_err(|e| AppError::new("WINDOW_STATE_ERROR", e.to_string()))?;
+                    
+                    // Start a new diagonal further right once a window would
+                    // run off the bottom, and back at the left edge once that
+                    // runs off the side too
+                    if y + size.height > top + height {
+                        column_start += STEP * 4.0;
+                        x = column_start;
+                        y = top;
+                    }
+                    if x + size.width > left + width {
+                        column_start = left;
+                        x = left;
+                        y = top;
+                    }
+                    
+                    update(window, x, y, None)?;
+                    window.set_focus()
+                        .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+                    x += STEP;
+                    y += STEP;
+                }
+            }
+            FloatingLayout::Tile => {
+                let count = windows.len();
+                let columns = (count as f64).sqrt().ceil() as usize;
+                let rows = count.div_ceil(columns);
+                let cell_width = width / columns as f64;
+                let cell_height = height / rows as f64;
+                
+                for (index, (_, window)) in windows.iter().enumerate() {
+                    let column = (index % columns) as f64;
+                    let row = (index / columns) as f64;
+                    update(
+                        window,
+                        left + column * cell_width,
+                        top + row * cell_height,
+                        Some((cell_width - MARGIN / 2.0, cell_height - MARGIN / 2.0)),
+                    )?;
+                }
+            }
+        }
         
         Ok(())
     }
+    
+    /// Pins every floating note on top, or unpins them all if they already
+    /// are. Returns the new always-on-top state.
+    pub fn toggle_all_always_on_top(app: &AppHandle) -> AppResult<bool> {
+        let windows = Self::floating_windows(app);
+        let on_top = !windows.iter().all(|(_, window)| window.is_always_on_top().unwrap_or(false));
+        let repository = FloatingWindowRepository::new(app.state::<AppState>().db());
+        
+        for (note_id, window) in &windows {
+            window.set_always_on_top(on_top)
+                .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+            repository.save_always_on_top(*note_id, on_top)?;
+        }
+        
+        Ok(on_top)
+    }
+    
+    fn floating_windows(app: &AppHandle) -> Vec<(i64, WebviewWindow)> {
+        app.state::<FloatingWindowRegistry>()
+            .entries()
+            .into_iter()
+            .filter_map(|(note_id, label)| app.get_webview_window(&label).map(|window| (note_id, window)))
+            .collect()
+    }
+    
+    fn current_monitor(app: &AppHandle) -> AppResult<Monitor> {
+        let under_cursor = app.cursor_position().ok()
+            .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten());
+        
+        match under_cursor {
+            Some(monitor) => Ok(monitor),
+            None => app.primary_monitor()
+                .map_err(|e| AppError::new("WINDOW_STATE_ERROR", e.to_string()))?
+                .ok_or_else(|| AppError::new("WINDOW_STATE_ERROR", "No monitor available")),
+        }
+    }
+    
+    /// The opacity a window should render at: the note's own override for
+    /// floating notes, the transparency preference otherwise
+    pub fn get_window_opacity(app: &AppHandle, label: &str) -> AppResult<f64> {
+        let override_opacity = match app.state::<FloatingWindowRegistry>().note_for_label(label) {
+            Some(note_id) => FloatingWindowRepository::new(app.state::<AppState>().db())
+                .get_state(note_id)?
+                .and_then(|state| state.opacity),
+            None => None,
+        };
+        
+        Ok(clamp_opacity(override_opacity.unwrap_or(Self::window_preferences(app).transparency)))
+    }
+    
+    /// Sets a floating note's opacity, clamped to 50–100%, and keeps it as
+    /// the note's override; `None` drops the override. Only floating notes
+    /// render the opacity, so other windows are refused.
+    pub fn set_window_opacity(app: &AppHandle, label: &str, value: Option<f64>) -> AppResult<f64> {
+        let window = app.get_webview_window(label)
+            .ok_or_else(|| AppError::new("NOT_FOUND", format!("Window '{}' not found", label)))?;
+        let note_id = app.state::<FloatingWindowRegistry>()
+            .note_for_label(label)
+            .ok_or_else(|| AppError::new("NOT_FLOATING_WINDOW", format!("Window '{}' is not a floating note", label)))?;
+        let value = value.map(clamp_opacity);
+        
+        FloatingWindowRepository::new(app.state::<AppState>().db())
+            .save_opacity(note_id, value)?;
+        
+        let opacity = match value {
+            Some(opacity) => opacity,
+            None => Self::get_window_opacity(app, label)?,
+        };
+        window.emit_to(label, OPACITY_CHANGED_EVENT, opacity)
+            .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+        
+        Ok(opacity)
+    }
+    
+    /// Pushes a changed transparency preference to the floating notes that
+    /// don't override it
+    pub fn refresh_opacity(app: &AppHandle) -> AppResult<()> {
+        for (_, window) in Self::floating_windows(app) {
+            let opacity = Self::get_window_opacity(app, window.label())?;
+            window.emit_to(window.label(), OPACITY_CHANGED_EVENT, opacity)
+                .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+        }
+        
+        Ok(())
+    }
+    
+    fn window_preferences(app: &AppHandle) -> WindowPreferences {
+        PreferencesManager::new(app).load().window
+    }
+    
+    fn focus_window(window: &WebviewWindow) -> AppResult<()> {
+        let _ = window.unminimize();
+        window.show()
+            .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+        window.set_focus()
+            .map_err(|e| AppError::new("WINDOW_UPDATE_ERROR", e.to_string()))?;
+        
+        Ok(())
+    }
+    
+    /// Reopens the floating notes that were open when the app last quit
+    pub fn restore_floating_notes(app: &AppHandle) -> AppResult<()> {
+        let db = app.state::<AppState>().db();
+        let repository = FloatingWindowRepository::new(db.clone());
+        let notes = NoteService::new(db);
+        
+        for state in repository.get_open_windows()? {
+            if notes.get_note(state.note_id)?.is_none() {
+                repository.set_open(state.note_id, false)?;
+                continue;
+            }
+            
+            if let Err(e) = Self::create_floating_note(app, state.note_id) {
+                eprintln!("Failed to restore floating note {}: {}", state.note_id, e.message);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Persists position and size as the window is moved or resized, and
+    /// forgets the window once the user closes it
+    fn track_floating_note(app: &AppHandle, window: &WebviewWindow, note_id: i64) {
+        let repository = FloatingWindowRepository::new(app.state::<AppState>().db());
+        let handle = window.clone();
+        
+        window.on_window_event(move |event| {
+            let result = match event {
+                WindowEvent::Moved(position) => {
+                    let scale = handle.scale_factor().unwrap_or(1.0);
+                    let position = position.to_logical::<f64>(scale);
+                    let monitor = handle.current_monitor().ok().flatten()
+                        .and_then(|monitor| monitor.name().cloned());
+                    repository.save_position(note_id, position.x, position.y, monitor)
+                }
+                WindowEvent::Resized(size) => {
+                    let scale = handle.scale_factor().unwrap_or(1.0);
+                    let size = size.to_logical::<f64>(scale);
+                    repository.save_size(note_id, size.width, size.height)
+                }
+                // Quitting the app doesn't request a close, so windows still
+                // open at exit stay marked open and are restored next launch
+                WindowEvent::CloseRequested { .. } => repository.set_open(note_id, false),
+                WindowEvent::Destroyed => {
+                    handle.state::<FloatingWindowRegistry>().unregister(note_id);
+                    Ok(())
+                }
+                _ => Ok(()),
+            };
+            
+            if let Err(e) = result {
+                eprintln!("Failed to save floating note {} window state: {}", note_id, e.message);
+            }
+        });
+    }
+    
+    fn monitor_available(app: &AppHandle, monitor: Option<&str>) -> bool {
+        let monitor = match monitor {
+            Some(monitor) => monitor,
+            None => return true,
+        };
+        
+        app.available_monitors()
+            .map(|monitors| monitors.iter().any(|m| m.name().map(String::as_str) == Some(monitor)))
+            .unwrap_or(false)
+    }
 }
\ No newline at end of file
@@ -1,35 +1,213 @@
 use tauri::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, Runtime, AppHandle, Emitter,
+    Manager, AppHandle, Wry,
 };
+use crate::core::AppState;
+use crate::features::notes::models::Note;
+use crate::features::notes::service::NoteService;
+use crate::features::preferences::PreferencesManager;
+use super::models::FloatingLayout;
+use super::service::WindowService;
 
-pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
-    let _ = TrayIconBuilder::with_id("extranuts-tray")
-        .tooltip("Extranuts")
-        .on_tray_icon_event(|tray, event| match event {
-            TrayIconEvent::Click {
+const TRAY_ID: &str = "extranuts-tray";
+const RECENT_NOTES: i64 = 5;
+// Long titles would stretch the menu across the screen
+const MAX_TITLE_CHARS: usize = 40;
+
+const NEW_NOTE: &str = "new_note";
+const QUICK_CAPTURE: &str = "quick_capture";
+const QUICK_SEARCH: &str = "quick_search";
+const TOGGLE_MAIN: &str = "toggle_main";
+const PREFERENCES: &str = "preferences";
+const QUIT: &str = "quit";
+const FLOATING_CASCADE: &str = "floating_cascade";
+const FLOATING_TILE: &str = "floating_tile";
+const FLOATING_TOGGLE_ON_TOP: &str = "floating_toggle_on_top";
+const FLOATING_CLOSE_ALL: &str = "floating_close_all";
+// Note items carry the note id after the prefix, e.g. "note:42"
+const NOTE_PREFIX: &str = "note:";
+
+pub fn create_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app)?;
+    let (tooltip, title) = tray_status(app);
+    
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(tooltip)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
                 button: MouseButton::Left,
                 button_state: MouseButtonState::Up,
                 ..
-            } => {
-                let app = tray.app_handle();
-                if let Some(window) = app.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.set_focus();
-                }
-            }
-            TrayIconEvent::Click {
-                button: MouseButton::Right,
-                button_state: MouseButtonState::Up,
-                ..
-            } => {
-                tray.app_handle()
-                    .emit("tray-menu", ())
-                    .expect("failed to emit tray menu event");
+            } = event {
+                WindowService::show_main_window(tray.app_handle());
             }
-            _ => {}
-        })
-        .build(app)?;
+        });
+    if let Some(title) = title {
+        builder = builder.title(title);
+    }
+    builder.build(app)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Rebuilds the tray menu and status so the note lists and counts stay
+/// current. Called after anything that creates, edits or deletes notes.
+pub fn refresh_tray(app: &AppHandle) {
+    let tray = match app.tray_by_id(TRAY_ID) {
+        Some(tray) => tray,
+        None => return,
+    };
+    
+    if let Err(e) = build_menu(app).and_then(|menu| tray.set_menu(Some(menu))) {
+        eprintln!("Failed to rebuild tray menu: {}", e);
+    }
+    
+    let (tooltip, title) = tray_status(app);
+    if let Err(e) = tray.set_tooltip(Some(tooltip)).and_then(|_| tray.set_title(title)) {
+        eprintln!("Failed to update tray status: {}", e);
+    }
+}
+
+/// Tooltip and optional menu bar title, e.g. "Extranuts — 42 notes · 3 pinned · Synced"
+fn tray_status(app: &AppHandle) -> (String, Option<String>) {
+    let prefs = PreferencesManager::new(app).load();
+    let stats = match NoteService::new(app.state::<AppState>().db()).get_note_stats() {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("Failed to count notes for tray: {}", e.message);
+            return ("Extranuts".to_string(), None);
+        }
+    };
+    
+    let mut parts = Vec::new();
+    if prefs.tray.show_total {
+        parts.push(format!("{} {}", stats.total, if stats.total == 1 { "note" } else { "notes" }));
+    }
+    if prefs.tray.show_pinned {
+        parts.push(format!("{} pinned", stats.pinned));
+    }
+    if prefs.tray.show_created_today {
+        parts.push(format!("{} today", stats.created_today));
+    }
+    if prefs.tray.show_sync_status {
+        let sync = &prefs.sync;
+        parts.push(if sync.last_error.is_some() {
+            "Sync error".to_string()
+        } else if sync.icloud_sync_enabled || sync.folder_sync_enabled {
+            "Synced".to_string()
+        } else {
+            "Sync off".to_string()
+        });
+    }
+    
+    let tooltip = if parts.is_empty() {
+        "Extranuts".to_string()
+    } else {
+        format!("Extranuts — {}", parts.join(" · "))
+    };
+    let title = prefs.tray.show_count_in_menu_bar.then(|| stats.total.to_string());
+    
+    (tooltip, title)
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let notes = NoteService::new(app.state::<AppState>().db());
+    let recent = notes.get_recent_notes(RECENT_NOTES).unwrap_or_else(|e| {
+        eprintln!("Failed to load recent notes for tray: {}", e.message);
+        Vec::new()
+    });
+    let pinned = notes.get_pinned_notes().unwrap_or_else(|e| {
+        eprintln!("Failed to load pinned notes for tray: {}", e.message);
+        Vec::new()
+    });
+    
+    let menu = Menu::with_items(app, &[
+        &MenuItem::with_id(app, NEW_NOTE, "New Note", true, None::<&str>)?,
+        &MenuItem::with_id(app, QUICK_CAPTURE, "Quick Capture…", true, None::<&str>)?,
+        &MenuItem::with_id(app, QUICK_SEARCH, "Quick Search", true, None::<&str>)?,
+        // The window can be shown or hidden without the menu knowing, so the
+        // label doesn't try to track which action the toggle will take
+        &MenuItem::with_id(app, TOGGLE_MAIN, "Show/Hide Extranuts", true, None::<&str>)?,
+        &PredefinedMenuItem::separator(app)?,
+    ])?;
+    
+    if recent.is_empty() {
+        menu.append(&MenuItem::new(app, "No Recent Notes", false, None::<&str>)?)?;
+    } else {
+        menu.append(&MenuItem::new(app, "Recent Notes", false, None::<&str>)?)?;
+        for note in &recent {
+            menu.append(&note_item(app, note)?)?;
+        }
+    }
+    
+    let pinned_menu = Submenu::new(app, "Pinned Notes", !pinned.is_empty())?;
+    for note in &pinned {
+        pinned_menu.append(&note_item(app, note)?)?;
+    }
+    
+    let floating_menu = Submenu::with_items(app, "Floating Notes", true, &[
+        &MenuItem::with_id(app, FLOATING_CASCADE, "Cascade", true, None::<&str>)?,
+        &MenuItem::with_id(app, FLOATING_TILE, "Tile", true, None::<&str>)?,
+        &MenuItem::with_id(app, FLOATING_TOGGLE_ON_TOP, "Toggle Always on Top", true, None::<&str>)?,
+        &MenuItem::with_id(app, FLOATING_CLOSE_ALL, "Close All", true, None::<&str>)?,
+    ])?;
+    
+    menu.append_items(&[
+        &PredefinedMenuItem::separator(app)?,
+        &pinned_menu,
+        &floating_menu,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, PREFERENCES, "Preferences…", true, Some("CmdOrCtrl+,"))?,
+        &MenuItem::with_id(app, QUIT, "Quit Extranuts", true, Some("CmdOrCtrl+Q"))?,
+    ])?;
+    
+    Ok(menu)
+}
+
+fn note_item(app: &AppHandle, note: &Note) -> tauri::Result<MenuItem<Wry>> {
+    let title = if note.title.trim().is_empty() {
+        "Untitled".to_string()
+    } else if note.title.chars().count() > MAX_TITLE_CHARS {
+        format!("{}…", note.title.chars().take(MAX_TITLE_CHARS).collect::<String>())
+    } else {
+        note.title.clone()
+    };
+    
+    let id = format!("{}{}", NOTE_PREFIX, note.id.unwrap_or_default());
+    MenuItem::with_id(app, id, title, true, None::<&str>)
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    
+    let result = match id {
+        NEW_NOTE => WindowService::request_from_main(app, "new-note-requested"),
+        QUICK_CAPTURE => WindowService::open_quick_capture(app),
+        QUICK_SEARCH => WindowService::request_from_main(app, "quick-search-requested"),
+        PREFERENCES => WindowService::request_from_main(app, "preferences-requested"),
+        TOGGLE_MAIN => {
+            WindowService::toggle_main_window(app);
+            Ok(())
+        }
+        QUIT => {
+            app.exit(0);
+            Ok(())
+        }
+        FLOATING_CASCADE => WindowService::arrange_floating_notes(app, FloatingLayout::Cascade),
+        FLOATING_TILE => WindowService::arrange_floating_notes(app, FloatingLayout::Tile),
+        FLOATING_TOGGLE_ON_TOP => WindowService::toggle_all_always_on_top(app).map(|_| ()),
+        FLOATING_CLOSE_ALL => WindowService::close_all_floating_notes(app).map(|_| ()),
+        _ => match id.strip_prefix(NOTE_PREFIX).and_then(|note_id| note_id.parse::<i64>().ok()) {
+            Some(note_id) => WindowService::create_floating_note(app, note_id),
+            None => Ok(()),
+        },
+    };
+    
+    if let Err(e) = result {
+        eprintln!("Tray menu action '{}' failed: {}", id, e.message);
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Serialize, Deserialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DatabaseConfig {
     pub path: PathBuf,
+    pub device_id: String, // Written to the change journal for every mutation
 }
 
 pub struct Database {
@@ -41,4 +42,8 @@ impl Database {
     pub fn path(&self) -> &PathBuf {
         &self.config.path
     }
+    
+    pub fn device_id(&self) -> &str {
+        &self.config.device_id
+    }
 }
\ No newline at end of file
@@ -1,6 +1,10 @@
-use rusqlite::Result;
+use rusqlite::{params, Connection, Result};
+use uuid::Uuid;
 use super::Database;
 
+/// Tables that carry a stable `uuid` alongside their integer primary key
+const UUID_TABLES: [&str; 4] = ["notes", "categories", "tags", "selectors"];
+
 pub fn run_migrations(db: &Database) -> Result<()> {
     let conn = db.connection();
     
@@ -8,9 +12,13 @@ pub fn run_migrations(db: &Database) -> Result<()> {
         -- Categories table
         CREATE TABLE IF NOT EXISTS categories (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
+            uuid TEXT,
             name TEXT NOT NULL,
             color TEXT,
             parent_id INTEGER,
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            icon TEXT,
+            description TEXT,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (parent_id) REFERENCES categories(id) ON DELETE CASCADE,
             UNIQUE(name, parent_id)
@@ -19,11 +27,14 @@ pub fn run_migrations(db: &Database) -> Result<()> {
         -- Notes table
         CREATE TABLE IF NOT EXISTS notes (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
+            uuid TEXT,
             title TEXT NOT NULL,
             content TEXT,
             category_id INTEGER,
             selector_id INTEGER,
             is_pinned INTEGER NOT NULL DEFAULT 0,
+            sort_order INTEGER,
+            auto_title INTEGER NOT NULL DEFAULT 0,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
@@ -32,6 +43,7 @@ pub fn run_migrations(db: &Database) -> Result<()> {
         -- Tags table
         CREATE TABLE IF NOT EXISTS tags (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
+            uuid TEXT,
             name TEXT NOT NULL UNIQUE,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         );
@@ -85,20 +97,153 @@ pub fn run_migrations(db: &Database) -> Result<()> {
     // This will fail silently if the column already exists
     let _ = conn.execute("ALTER TABLE notes ADD COLUMN selector_id INTEGER", []);
     
+    // Migration to add manual ordering of categories among their siblings
+    let _ = conn.execute("ALTER TABLE categories ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0", []);
+    
+    // Migration to add an optional manual order for notes within a category
+    let _ = conn.execute("ALTER TABLE notes ADD COLUMN sort_order INTEGER", []);
+    
+    // Migration to let titles follow the content; existing notes keep theirs
+    let _ = conn.execute("ALTER TABLE notes ADD COLUMN auto_title INTEGER NOT NULL DEFAULT 0", []);
+    
+    // Migration to keep the icon and description a category was created with
+    let _ = conn.execute("ALTER TABLE categories ADD COLUMN icon TEXT", []);
+    let _ = conn.execute("ALTER TABLE categories ADD COLUMN description TEXT", []);
+    
+    // User-defined category presets, listed after the built-in ones
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS category_presets (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            color TEXT NOT NULL,
+            description TEXT NOT NULL DEFAULT '',
+            icon TEXT NOT NULL DEFAULT '',
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+        )
+    ", [])?;
+    
     // Create selectors table for custom names
     conn.execute("
         CREATE TABLE IF NOT EXISTS selectors (
             id INTEGER PRIMARY KEY,
+            uuid TEXT,
             name TEXT NOT NULL,
+            color TEXT,
+            icon TEXT,
+            shortcut TEXT,
             created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         )
     ", [])?;
     
+    // Migration to add per-selector appearance to existing databases
+    let _ = conn.execute("ALTER TABLE selectors ADD COLUMN color TEXT", []);
+    let _ = conn.execute("ALTER TABLE selectors ADD COLUMN icon TEXT", []);
+    let _ = conn.execute("ALTER TABLE selectors ADD COLUMN shortcut TEXT", []);
+    
     // Create index for selector lookups
     conn.execute("
         CREATE INDEX IF NOT EXISTS idx_notes_selector ON notes(selector_id)
     ", [])?;
     
+    // Migration to add stable UUIDs to existing databases
+    // Integer ids are kept for internal joins, UUIDs identify rows across devices
+    for table in UUID_TABLES {
+        let _ = conn.execute(&format!("ALTER TABLE {} ADD COLUMN uuid TEXT", table), []);
+        backfill_uuids(conn, table)?;
+        conn.execute(
+            &format!("CREATE UNIQUE INDEX IF NOT EXISTS idx_{}_uuid ON {}(uuid)", table, table),
+            [],
+        )?;
+    }
+    
+    // Append-only change journal used for incremental sync and auditing
+    conn.execute_batch("
+        CREATE TABLE IF NOT EXISTS change_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            entity TEXT NOT NULL,
+            entity_id INTEGER NOT NULL,
+            entity_uuid TEXT,
+            operation TEXT NOT NULL,
+            before_json TEXT,
+            after_json TEXT,
+            device_id TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );
+        
+        CREATE INDEX IF NOT EXISTS idx_change_log_entity ON change_log(entity, entity_id);
+        
+        CREATE TRIGGER IF NOT EXISTS change_log_no_update BEFORE UPDATE ON change_log
+        BEGIN
+            SELECT RAISE(ABORT, 'change_log is append-only');
+        END;
+        
+        CREATE TRIGGER IF NOT EXISTS change_log_no_delete BEFORE DELETE ON change_log
+        BEGIN
+            SELECT RAISE(ABORT, 'change_log is append-only');
+        END;
+    ")?;
+    
+    // Last synced state of each note mirrored in the sync folder
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS sync_files (
+            note_uuid TEXT PRIMARY KEY,
+            folder TEXT,
+            file_name TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            synced_at TEXT NOT NULL
+        )
+    ", [])?;
+    
+    // Migration to tie each sync record to the folder it was synced with;
+    // older records have none and are dropped at the next sync
+    let _ = conn.execute("ALTER TABLE sync_files ADD COLUMN folder TEXT", []);
+    
+    // One-time setup steps already done on this database, e.g. seeding the selectors
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS app_meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )
+    ", [])?;
+    
+    // Geometry of floating note windows, restored on launch
+    conn.execute("
+        CREATE TABLE IF NOT EXISTS floating_windows (
+            note_id INTEGER PRIMARY KEY,
+            x REAL,
+            y REAL,
+            width REAL NOT NULL,
+            height REAL NOT NULL,
+            always_on_top INTEGER NOT NULL DEFAULT 1,
+            opacity REAL,
+            monitor TEXT,
+            is_open INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (note_id) REFERENCES notes(id) ON DELETE CASCADE
+        )
+    ", [])?;
+    
     Ok(())
+}
+
+fn backfill_uuids(conn: &Connection, table: &str) -> Result<()> {
+    let ids = conn
+        .prepare(&format!("SELECT id FROM {} WHERE uuid IS NULL", table))?
+        .query_map([], |row| row.get::<_, i64>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    
+    if ids.is_empty() {
+        return Ok(());
+    }
+    
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(&format!("UPDATE {} SET uuid = ?1 WHERE id = ?2", table))?;
+        for id in ids {
+            stmt.execute(params![Uuid::new_v4().to_string(), id])?;
+        }
+    }
+    tx.commit()
 }
\ No newline at end of file
@@ -4,6 +4,7 @@ mod infrastructure;
 mod features;
 
 use tauri::Manager;
+use uuid::Uuid;
 
 #[cfg(target_os = "macos")]
 use tauri::ActivationPolicy;
@@ -15,8 +16,11 @@ use infrastructure::{
     storage::{StoragePaths, StorageLocation},
 };
 use features::{
-    windows::tray::create_tray,
-    preferences::PreferencesManager,
+    windows::{registry::FloatingWindowRegistry, service::WindowService, tray::create_tray},
+    preferences::{PreferencesManager, PreferencesWarning},
+    selectors::repository::SelectorRepository,
+    shortcuts::{ShortcutRegistry, ShortcutService},
+    sync::{FolderWatcher, watcher::run_sync},
 };
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -24,6 +28,11 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(ShortcutService::handle)
+                .build(),
+        )
         .setup(|app| {
             #[cfg(target_os = "macos")]
             {
@@ -32,7 +41,20 @@ pub fn run() {
             
             // Initialize preferences
             let prefs_manager = PreferencesManager::new(app.handle());
-            let prefs = prefs_manager.load();
+            let loaded = prefs_manager.load_checked();
+            let mut prefs = loaded.preferences;
+            if let Some(ref warning) = loaded.warning {
+                eprintln!("{}", warning);
+            }
+            app.manage(PreferencesWarning::new(loaded.warning));
+            
+            // Assign a stable device id on first launch
+            if prefs.sync.device_id.is_empty() {
+                prefs.sync.device_id = Uuid::new_v4().to_string();
+                if let Err(e) = prefs_manager.save(&prefs) {
+                    eprintln!("Failed to save device id: {}", e.message);
+                }
+            }
             
             // Determine database location based on preferences
             let db_location = if prefs.sync.icloud_sync_enabled {
@@ -42,7 +64,10 @@ pub fn run() {
             };
             
             let db_path = StoragePaths::get_database_path(app.handle(), db_location);
-            let db_config = DatabaseConfig { path: db_path };
+            let db_config = DatabaseConfig {
+                path: db_path,
+                device_id: prefs.sync.device_id.clone(),
+            };
             
             // Initialize database
             let db = Database::new(db_config)
@@ -54,11 +79,41 @@ pub fn run() {
             
             // Initialize app state
             let app_state = AppState::new(db);
+            
+            // Seed the billiard ball set the first time this database is opened
+            if let Err(e) = SelectorRepository::new(app_state.db()).seed_defaults() {
+                eprintln!("Failed to seed selectors: {}", e.message);
+            }
             app.manage(app_state);
             
+            // Mirror notes into the sync folder and pick up external edits
+            let folder_watcher = FolderWatcher::default();
+            if prefs.sync.folder_sync_enabled {
+                if let Some(ref folder) = prefs.sync.folder_sync_path {
+                    let folder = std::path::PathBuf::from(folder);
+                    run_sync(app.handle(), folder.clone());
+                    if let Err(e) = folder_watcher.start(app.handle(), folder) {
+                        eprintln!("Failed to watch sync folder: {}", e.message);
+                    }
+                }
+            }
+            app.manage(folder_watcher);
+            
+            // Reopen floating notes left open at the last quit
+            app.manage(FloatingWindowRegistry::default());
+            if let Err(e) = WindowService::restore_floating_notes(app.handle()) {
+                eprintln!("Failed to restore floating notes: {}", e.message);
+            }
+            
             // Create system tray
             create_tray(app.handle())?;
             
+            // Register global shortcuts; a taken or invalid one shouldn't stop launch
+            app.manage(ShortcutRegistry::default());
+            if let Err(e) = ShortcutService::apply(app.handle(), &prefs.shortcuts) {
+                eprintln!("Failed to register global shortcuts: {}", e.message);
+            }
+            
             // Show main window
             let main_window = app.get_webview_window("main").unwrap();
             main_window.show()?;
@@ -69,24 +124,44 @@ pub fn run() {
             // Window management commands
             features::windows::create_floating_window,
             features::windows::create_floating_note,
+            features::windows::get_floating_note,
+            features::windows::list_floating_notes,
+            features::windows::focus_floating_note,
+            features::windows::close_all_floating_notes,
+            features::windows::arrange_floating_notes,
+            features::windows::toggle_all_always_on_top,
+            features::windows::get_window_opacity,
+            features::windows::set_window_opacity,
+            features::windows::open_quick_capture,
             features::windows::toggle_always_on_top,
             features::windows::show_in_menu_bar,
             
             // Note commands
             features::notes::create_note,
             features::notes::get_note,
+            features::notes::get_note_by_uuid,
             features::notes::search_notes,
+            features::notes::get_notes_grouped_by_selector,
             features::notes::get_all_notes,
             features::notes::update_note,
             features::notes::delete_note,
+            features::notes::reorder_notes,
+            features::notes::quick_capture,
             
             // Sync commands
             features::sync::get_sync_status,
             features::sync::toggle_icloud_sync,
+            features::sync::configure_folder_sync,
+            features::sync::sync_folder_now,
             
             // Preferences commands
             features::preferences::get_preferences,
             features::preferences::update_preferences,
+            features::preferences::take_preferences_warning,
+            
+            // Shortcut commands
+            features::shortcuts::update_shortcuts,
+            features::shortcuts::validate_shortcut,
             
             // Export commands
             features::export::export_to_obsidian,
@@ -95,20 +170,42 @@ pub fn run() {
             // Category commands
             features::categories::create_category,
             features::categories::get_category,
+            features::categories::get_category_by_uuid,
             features::categories::get_all_categories,
             features::categories::get_hierarchical_categories,
             features::categories::update_category,
+            features::categories::move_category,
+            features::categories::reorder_categories,
             features::categories::delete_category,
+            features::categories::merge_categories,
             features::categories::get_category_presets,
+            features::categories::get_color_palette,
             features::categories::create_category_from_preset,
+            features::categories::create_preset_set,
+            features::categories::create_category_preset,
+            features::categories::update_category_preset,
+            features::categories::delete_category_preset,
+            features::categories::reorder_category_presets,
             
             // Selector commands
             features::selectors::create_selector,
             features::selectors::update_selector_name,
+            features::selectors::update_selector_style,
             features::selectors::get_selector,
+            features::selectors::get_selector_by_uuid,
             features::selectors::get_all_selectors,
             features::selectors::delete_selector,
+            features::selectors::reassign_selector,
+            features::selectors::check_selector_integrity,
             features::selectors::selector_exists,
+            
+            // Change journal commands
+            features::journal::get_changes_since,
+            
+            // Undo commands
+            features::undo::undo_last,
+            features::undo::redo,
+            features::undo::get_undo_state,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
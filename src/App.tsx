@@ -27,6 +27,8 @@ import {
 import {
   preferences,
   loadPreferences,
+  preferencesWarning,
+  setPreferencesWarning,
   toggleDeleteConfirmation,
 } from "./stores/preferencesStore";
 import { createFullBackup } from "./utils/backup";
@@ -40,6 +42,7 @@ import ExportModal from "./components/ExportModal";
 import CategorySelector from "./components/CategorySelector";
 import CategoryManager from "./components/CategoryManager";
 import { categoriesService } from "./services/categories";
+import { notesService } from "./services/notes";
 import MarkdownPreview from "./components/MarkdownPreview";
 import EnhancedEditor from "./components/EnhancedEditor";
 import ThemeToggle from "./components/ThemeToggle";
@@ -139,6 +142,7 @@ const App: Component = () => {
     Array<{ id: number; name: string; color: string }>
   >([]);
   const [searchQuery, setSearchQuery] = createSignal("");
+  let searchInput: HTMLInputElement | undefined;
   const [showCategoryManager, setShowCategoryManager] = createSignal(false);
   const [titleColumnWidth, setTitleColumnWidth] = createSignal(200); // pixels
   const [isRenamingSelector, setIsRenamingSelector] = createSignal(false);
@@ -230,6 +234,14 @@ const App: Component = () => {
         await currentWindow.listen(TauriEvent.WINDOW_FOCUS, () => {
           console.log("Window focused");
         });
+        // Tray menu and global shortcut actions that need the main window
+        await currentWindow.listen("new-note-requested", () => createRegularNote());
+        await currentWindow.listen("quick-search-requested", () => {
+          searchInput?.focus();
+          searchInput?.select();
+        });
+        await currentWindow.listen("preferences-requested", () => setShowSettings(true));
+        await currentWindow.listen("note-captured", () => loadNotes());
         console.log("Window event listeners set up");
       } catch (eventError) {
         console.error("Failed to set up window events:", eventError);
@@ -271,11 +283,7 @@ const App: Component = () => {
 
       updateNote(note.id, { isFloating: true });
 
-      await invoke("create_floating_window", {
-        label: `note-${note.id}`,
-        width: 400,
-        height: 300,
-      });
+      await notesService.openFloatingNote(note.id);
 
       setSelectedNote(note);
     } catch (error) {
@@ -603,6 +611,20 @@ const App: Component = () => {
           <p class="text-red-400">{error()}</p>
         </div>
       </Show>
+
+      {/* Preferences repaired at launch */}
+      <Show when={preferencesWarning()}>
+        <div class="fixed top-4 left-1/2 -translate-x-1/2 max-w-lg bg-amber-500/20 border border-amber-500/50 rounded-lg p-4 z-50 flex items-start gap-3">
+          <p class="text-amber-300 text-sm">{preferencesWarning()}</p>
+          <button
+            onClick={() => setPreferencesWarning(null)}
+            class="text-amber-300 hover:text-white text-sm"
+            title="Dismiss"
+          >
+            ✕
+          </button>
+        </div>
+      </Show>
       {/* Top Header */}
       <div class="h-16 sidebar-glass flex items-center justify-between px-6 border-b border-macos-border drag-region">
         <h1 class="text-xl font-semibold">Extranuts</h1>
@@ -1046,6 +1068,7 @@ const App: Component = () => {
         <div class="p-4 h-full">
           <div class="mb-3">
             <input
+              ref={searchInput}
               type="text"
               value={searchQuery()}
               onInput={(e) => setSearchQuery(e.target.value)}
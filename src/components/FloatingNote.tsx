@@ -0,0 +1,95 @@
+import { Component, createSignal, onCleanup, onMount, Show } from 'solid-js'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { notesService } from '../services/notes'
+import type { Note } from '../types'
+
+const SAVE_DELAY = 500
+
+// Fenêtre flottante d'une note : le backend la retrouve depuis le label de la fenêtre
+export const FloatingNote: Component = () => {
+  const [note, setNote] = createSignal<Note | null>(null)
+  const [content, setContent] = createSignal('')
+  const [opacity, setOpacity] = createSignal(1)
+  const [error, setError] = createSignal('')
+  let saveTimer: number | undefined
+  let unlisten: (() => void) | undefined
+
+  onMount(async () => {
+    try {
+      const loaded = await notesService.getFloatingNote()
+      setNote(loaded)
+      setContent(loaded.content)
+      setOpacity(await notesService.getWindowOpacity())
+    } catch (err) {
+      setError(String(err))
+    }
+
+    // Transparence réglée depuis les préférences ou le menu de la fenêtre
+    unlisten = await getCurrentWindow().listen<number>('window-opacity-changed', (event) => {
+      setOpacity(event.payload)
+    })
+  })
+
+  const save = async () => {
+    const current = note()
+    if (!current) return
+    try {
+      await notesService.updateNote(current.id, {
+        content: content(),
+        categoryId: current.categoryId,
+      })
+      // Le titre peut suivre la première ligne du contenu
+      setNote(await notesService.getFloatingNote())
+      setError('')
+    } catch (err) {
+      setError(String(err))
+    }
+  }
+
+  const scheduleSave = () => {
+    window.clearTimeout(saveTimer)
+    saveTimer = window.setTimeout(() => {
+      saveTimer = undefined
+      save()
+    }, SAVE_DELAY)
+  }
+
+  // Enregistre tout de suite ce qui attendait encore
+  const flush = () => {
+    if (saveTimer === undefined) return
+    window.clearTimeout(saveTimer)
+    saveTimer = undefined
+    save()
+  }
+
+  onCleanup(() => {
+    unlisten?.()
+    flush()
+  })
+
+  return (
+    <div
+      class="h-screen w-screen flex flex-col text-macos-text rounded-lg overflow-hidden"
+      style={{ 'background-color': `rgba(30, 30, 30, ${opacity()})` }}
+    >
+      <div class="h-8 px-3 flex items-center drag-region border-b border-macos-border">
+        <span class="text-sm font-medium truncate">{note()?.title ?? ''}</span>
+      </div>
+      <Show
+        when={note()}
+        fallback={<div class="p-3 text-xs text-red-400">{error()}</div>}
+      >
+        <textarea
+          value={content()}
+          onInput={(e) => {
+            setContent(e.currentTarget.value)
+            scheduleSave()
+          }}
+          onBlur={flush}
+          class="flex-1 w-full p-3 text-sm bg-transparent outline-none resize-none"
+        />
+        {error() && <div class="px-3 py-1 text-xs text-red-400 truncate">{error()}</div>}
+      </Show>
+    </div>
+  )
+}
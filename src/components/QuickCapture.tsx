@@ -0,0 +1,52 @@
+import { Component, createSignal, onMount } from 'solid-js'
+import { getCurrentWindow } from '@tauri-apps/api/window'
+import { notesService } from '../services/notes'
+
+// Fenêtre de capture rapide : une ligne, avec #tag @Catégorie !sélecteur
+export const QuickCapture: Component = () => {
+  const [text, setText] = createSignal('')
+  const [error, setError] = createSignal('')
+  const [isSaving, setIsSaving] = createSignal(false)
+  let input: HTMLInputElement | undefined
+
+  onMount(() => {
+    input?.focus()
+    // Se ferme en perdant le focus, comme Spotlight
+    getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (!focused && !isSaving()) getCurrentWindow().close()
+    })
+  })
+
+  const submit = async () => {
+    if (!text().trim() || isSaving()) return
+    setIsSaving(true)
+    try {
+      await notesService.quickCapture(text())
+      await getCurrentWindow().close()
+    } catch (err) {
+      setError(String(err))
+      setIsSaving(false)
+    }
+  }
+
+  return (
+    <div class="h-screen w-screen p-2">
+      <input
+        ref={input}
+        type="text"
+        value={text()}
+        onInput={(e) => {
+          setText(e.currentTarget.value)
+          setError('')
+        }}
+        onKeyDown={(e) => {
+          if (e.key === 'Enter') submit()
+          if (e.key === 'Escape') getCurrentWindow().close()
+        }}
+        placeholder="Buy milk #errands @Personal !3"
+        class="w-full px-3 py-2 text-sm bg-macos-hover border border-macos-border rounded-lg outline-none focus:border-blue-500"
+      />
+      {error() && <div class="mt-1 px-1 text-xs text-red-400 truncate">{error()}</div>}
+    </div>
+  )
+}
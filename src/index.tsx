@@ -2,7 +2,17 @@
 import { render } from 'solid-js/web'
 import './index.css'
 import App from './App'
+import { QuickCapture } from './components/QuickCapture'
+import { FloatingNote } from './components/FloatingNote'
 
 const root = document.getElementById('root')
 
-render(() => <App />, root!)
\ No newline at end of file
+const params = new URLSearchParams(window.location.search)
+
+const View = () => {
+  if (params.has('quickCapture')) return <QuickCapture />
+  if (params.has('floating')) return <FloatingNote />
+  return <App />
+}
+
+render(() => <View />, root!)
\ No newline at end of file
@@ -25,6 +25,7 @@ import {
 import { selectorsStore } from '../stores/selectorsStore'
 import { themeStore } from '../stores/themeStore'
 import { categoriesService } from '../services/categories'
+import { notesService } from '../services/notes'
 
 // Import modules
 import { HeaderLogo, HeaderActions } from './header'
@@ -174,11 +175,7 @@ const AppOrchestrator: Component = () => {
 
       await updateNote(note.id, { isFloating: true })
 
-      await invoke("create_floating_window", {
-        label: `note-${note.id}`,
-        width: 400,
-        height: 300,
-      })
+      await notesService.openFloatingNote(note.id)
 
       setSelectedNote(note)
     } catch (error) {
@@ -4,7 +4,10 @@ export interface Category {
   id?: number
   name: string
   color: string
+  text_color: string
   parent_id?: number
+  icon?: string
+  description?: string
   created_at: string
   subcategories?: Category[]
 }
@@ -13,6 +16,8 @@ export interface CreateCategoryRequest {
   name: string
   color: string
   parent_id?: number
+  icon?: string
+  description?: string
 }
 
 export interface UpdateCategoryRequest {
@@ -20,13 +25,46 @@ export interface UpdateCategoryRequest {
   name: string
   color: string
   parent_id?: number
+  icon?: string
+  description?: string
+}
+
+export type DeleteStrategy =
+  | { type: 'orphan' }
+  | { type: 'move_to_parent' }
+  | { type: 'reassign'; target_id: number }
+  | { type: 'delete_notes' }
+
+export interface DeleteCategorySummary {
+  dry_run: boolean
+  destination_id: number | null
+  deleted_categories: number[]
+  moved_categories: number[]
+  moved_notes: number
+  orphaned_notes: number
+  deleted_notes: number
+  conflicts: string[]
+}
+
+export interface PaletteColor {
+  name: string
+  color: string
 }
 
 export interface CategoryPreset {
+  id: number | null
   name: string
   color: string
   description: string
   icon: string
+  is_builtin: boolean
+}
+
+export interface CategoryPresetRequest {
+  name: string
+  color: string
+  description?: string
+  icon?: string
 }
 
 export const categoriesService = {
@@ -50,8 +88,22 @@ export const categoriesService = {
     return await invoke<Category>('update_category', { request })
   },
 
-  async deleteCategory(id: number): Promise<void> {
-    await invoke('delete_category', { id })
+  // Pass the dry-run summary the user confirmed so a stale preview is rejected
+  async deleteCategory(
+    id: number,
+    strategy?: DeleteStrategy,
+    dryRun = false,
+    confirmed?: DeleteCategorySummary
+  ): Promise<DeleteCategorySummary> {
+    return await invoke<DeleteCategorySummary>('delete_category', { id, strategy, dryRun, confirmed })
+  },
+
+  async mergeCategories(sourceId: number, targetId: number): Promise<Category> {
+    return await invoke<Category>('merge_categories', { sourceId, targetId })
+  },
+
+  async getColorPalette(): Promise<PaletteColor[]> {
+    return await invoke<PaletteColor[]>('get_color_palette')
   },
 
   async getCategoryPresets(): Promise<CategoryPreset[]> {
@@ -60,5 +112,25 @@ export const categoriesService = {
 
   async createFromPreset(presetName: string): Promise<Category> {
     return await invoke<Category>('create_category_from_preset', { presetName })
+  },
+
+  async createPresetSet(presetNames?: string[]): Promise<Category[]> {
+    return await invoke<Category[]>('create_preset_set', { presetNames })
+  },
+
+  async createPreset(request: CategoryPresetRequest): Promise<CategoryPreset> {
+    return await invoke<CategoryPreset>('create_category_preset', { request })
+  },
+
+  async updatePreset(id: number, request: CategoryPresetRequest): Promise<CategoryPreset> {
+    return await invoke<CategoryPreset>('update_category_preset', { id, request })
+  },
+
+  async deletePreset(id: number): Promise<void> {
+    await invoke('delete_category_preset', { id })
+  },
+
+  async reorderPresets(orderedIds: number[]): Promise<void> {
+    await invoke('reorder_category_presets', { orderedIds })
   }
 }
\ No newline at end of file
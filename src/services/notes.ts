@@ -1,5 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Note as BackendNote, CreateNoteRequest, UpdateNoteRequest, SearchOptions } from '../types/models';
+import type { Note as BackendNote, CreateNoteRequest, UpdateNoteRequest, SearchOptions, SyncStatus, FloatingWindowInfo, FloatingLayout } from '../types/models';
 import type { Note as FrontendNote } from '../types';
 
 // Convert backend note to frontend note format
@@ -14,6 +14,7 @@ function convertNote(backendNote: BackendNote): FrontendNote {
     selectorId: backendNote.selector_id,
     tags: backendNote.tags.map(t => t.name),
     isPinned: backendNote.is_pinned,
+    autoTitle: backendNote.auto_title,
     isFloating: false, // Will be managed by frontend
   };
 }
@@ -64,14 +65,18 @@ export const notesService = {
     const currentNote = await this.getNote(id);
     if (!currentNote) return;
     
+    // Saves that don't mention the category keep it; an empty id clears it
+    const categoryId = 'categoryId' in updates ? updates.categoryId : currentNote.categoryId;
+    
     const request: UpdateNoteRequest = {
       id: numericId,
       title: updates.title ?? currentNote.title,
       content: updates.content ?? currentNote.content,
-      category_id: updates.categoryId ? parseInt(updates.categoryId) : undefined,
+      category_id: categoryId ? parseInt(categoryId) : undefined,
       selector_id: updates.selectorId ?? currentNote.selectorId,
-      tags: [], // Tags not implemented yet
+      tags: updates.tags ?? currentNote.tags,
       is_pinned: updates.isPinned ?? currentNote.isPinned,
+      auto_title: updates.autoTitle,
     };
     
     console.log('🔧 notesService.updateNote - Request avec selector_id:', {
@@ -100,12 +105,64 @@ export const notesService = {
       console.error('delete_note command failed:', error);
       throw error;
     }
+  },
+
+  // Parses #tag, @Category and !selector markers out of the line
+  async quickCapture(text: string): Promise<FrontendNote> {
+    const backendNote = await invoke<BackendNote>('quick_capture', { text });
+    return convertNote(backendNote);
+  },
+
+  async openQuickCapture(): Promise<void> {
+    await invoke('open_quick_capture');
+  },
+
+  // Opens the note in a floating window, or focuses the one already open
+  async openFloatingNote(id: string): Promise<void> {
+    await invoke('create_floating_note', { noteId: parseInt(id) });
+  },
+
+  // Loads the note shown by the current floating window
+  async getFloatingNote(): Promise<FrontendNote> {
+    const backendNote = await invoke<BackendNote>('get_floating_note');
+    return convertNote(backendNote);
+  },
+
+  async listFloatingNotes(): Promise<FloatingWindowInfo[]> {
+    return await invoke<FloatingWindowInfo[]>('list_floating_notes');
+  },
+
+  async focusFloatingNote(id: string): Promise<void> {
+    await invoke('focus_floating_note', { noteId: parseInt(id) });
+  },
+
+  async closeAllFloatingNotes(): Promise<number> {
+    return await invoke<number>('close_all_floating_notes');
+  },
+
+  async arrangeFloatingNotes(layout: FloatingLayout): Promise<void> {
+    await invoke('arrange_floating_notes', { layout });
+  },
+
+  // Returns whether the floating notes are now pinned on top
+  async toggleAllAlwaysOnTop(): Promise<boolean> {
+    return await invoke<boolean>('toggle_all_always_on_top');
+  },
+
+  // Opacity of the current window, updates arrive as 'window-opacity-changed'
+  async getWindowOpacity(): Promise<number> {
+    return await invoke<number>('get_window_opacity');
+  },
+
+  // Floating notes only. Clamped to 0.5–1; null follows the preference again
+  async setWindowOpacity(label: string, value: number | null): Promise<number> {
+    return await invoke<number>('set_window_opacity', { label, value });
   }
 };
 
 export const syncService = {
-  async getSyncStatus(): Promise<boolean> {
-    return await invoke<boolean>('get_sync_status');
+  async getSyncStatus(): Promise<SyncStatus> {
+    return await invoke<SyncStatus>('get_sync_status');
   },
 
   async toggleICloudSync(enabled: boolean): Promise<string> {
@@ -18,6 +18,22 @@ export interface WindowPreferences {
   transparency: number
 }
 
+export interface TrayPreferences {
+  show_total: boolean
+  show_pinned: boolean
+  show_created_today: boolean
+  show_sync_status: boolean
+  show_count_in_menu_bar: boolean
+}
+
+// Accelerators such as "CmdOrCtrl+Shift+N", null for no shortcut
+export interface ShortcutPreferences {
+  new_note: string | null
+  quick_search: string | null
+  quick_capture: string | null
+  toggle_main_window: string | null
+}
+
 export interface SyncSettings {
   icloud_sync_enabled: boolean
 }
@@ -27,11 +43,14 @@ export interface ExportPreferences {
 }
 
 export interface Preferences {
+  version: number // Schema version, migrated by the backend on load
   sync: SyncSettings
   window: WindowPreferences
   editor: EditorPreferences
   export: ExportPreferences
   appearance: AppearancePreferences
+  tray: TrayPreferences
+  shortcuts: ShortcutPreferences
 }
 
 export const preferencesService = {
@@ -39,10 +58,24 @@ export const preferencesService = {
     return await invoke<Preferences>('get_preferences')
   },
 
+  // Set once at launch when the preferences file had to be repaired
+  async takeWarning(): Promise<string | null> {
+    return await invoke<string | null>('take_preferences_warning')
+  },
+
   async updatePreferences(preferences: Preferences): Promise<void> {
     await invoke('update_preferences', { preferences })
   },
 
+  // Rebinds the global shortcuts right away; rejects conflicts and invalid accelerators
+  async updateShortcuts(shortcuts: ShortcutPreferences): Promise<void> {
+    await invoke('update_shortcuts', { shortcuts })
+  },
+
+  async validateShortcut(accelerator: string): Promise<void> {
+    await invoke('validate_shortcut', { accelerator })
+  },
+
   async updateEditorPreferences(editor: Partial<EditorPreferences>): Promise<void> {
     const current = await this.getPreferences()
     const updated = {
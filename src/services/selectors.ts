@@ -3,6 +3,10 @@ import { invoke } from "@tauri-apps/api/core";
 export interface Selector {
   id: number;
   name: string;
+  color: string;
+  icon: string | null;
+  shortcut: string | null;
+  note_count: number;
   created_at: string;
   updated_at: string;
 }
@@ -12,6 +16,22 @@ export interface UpdateSelectorRequest {
   name: string;
 }
 
+export interface UpdateSelectorStyleRequest {
+  id: number;
+  color: string;
+  icon?: string;
+  shortcut?: string;
+}
+
+export type SelectorDeletePolicy =
+  | { type: 'clear' }
+  | { type: 'reassign'; to: number };
+
+export interface SelectorIntegrityReport {
+  dangling: { selector_id: number; note_ids: number[] }[];
+  repaired: boolean;
+}
+
 /**
  * Met à jour le nom d'un sélecteur dans la base de données
  */
@@ -59,6 +79,34 @@ export async function getAllSelectors(): Promise<Selector[]> {
   }
 }
 
+/**
+ * Met à jour la couleur, l'icône et le raccourci d'un sélecteur
+ */
+export async function updateSelectorStyle(request: UpdateSelectorStyleRequest): Promise<Selector> {
+  return await invoke<Selector>('update_selector_style', { request })
+}
+
+/**
+ * Supprime un sélecteur, en vidant ou en réassignant ses notes
+ */
+export async function deleteSelector(id: number, policy?: SelectorDeletePolicy): Promise<void> {
+  await invoke('delete_selector', { id, policy })
+}
+
+/**
+ * Déplace toutes les notes d'un sélecteur vers un autre
+ */
+export async function reassignSelector(from: number, to: number): Promise<number> {
+  return await invoke<number>('reassign_selector', { from, to })
+}
+
+/**
+ * Vérifie que chaque note référence un sélecteur existant
+ */
+export async function checkSelectorIntegrity(repair?: 'clear' | 'restore'): Promise<SelectorIntegrityReport> {
+  return await invoke<SelectorIntegrityReport>('check_selector_integrity', { repair })
+}
+
 /**
  * Charge les noms de sélecteurs personnalisés depuis le backend
  * Retourne un Map pour faciliter la recherche par ID
@@ -3,6 +3,7 @@ import { preferencesService, type Preferences } from '../services/preferences'
 
 // Default preferences
 const defaultPreferences: Preferences = {
+  version: 2,
   sync: {
     icloud_sync_enabled: false
   },
@@ -23,15 +24,35 @@ const defaultPreferences: Preferences = {
   },
   appearance: {
     theme: 'dark'
+  },
+  tray: {
+    show_total: true,
+    show_pinned: true,
+    show_created_today: false,
+    show_sync_status: true,
+    show_count_in_menu_bar: false
+  },
+  shortcuts: {
+    new_note: 'CmdOrCtrl+Shift+N',
+    quick_search: 'CmdOrCtrl+Shift+F',
+    quick_capture: 'CmdOrCtrl+Shift+Space',
+    toggle_main_window: null
   }
 }
 
 export const [preferences, setPreferences] = createSignal<Preferences>(defaultPreferences)
+export const [preferencesWarning, setPreferencesWarning] = createSignal<string | null>(null)
 
 export async function loadPreferences() {
   try {
     const prefs = await preferencesService.getPreferences()
     setPreferences(prefs)
+    
+    const warning = await preferencesService.takeWarning()
+    if (warning) {
+      console.warn(warning)
+      setPreferencesWarning(warning)
+    }
   } catch (err) {
     console.error('Failed to load preferences:', err)
   }
@@ -40,11 +61,14 @@ export async function loadPreferences() {
 export async function updatePreferences(updates: Partial<Preferences>) {
   const current = preferences()
   const updated = {
+    version: current.version,
     sync: { ...current.sync, ...(updates.sync || {}) },
     window: { ...current.window, ...(updates.window || {}) },
     editor: { ...current.editor, ...(updates.editor || {}) },
     export: { ...current.export, ...(updates.export || {}) },
-    appearance: { ...current.appearance, ...(updates.appearance || {}) }
+    appearance: { ...current.appearance, ...(updates.appearance || {}) },
+    tray: { ...current.tray, ...(updates.tray || {}) },
+    shortcuts: { ...current.shortcuts, ...(updates.shortcuts || {}) }
   }
   
   try {
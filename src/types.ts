@@ -8,6 +8,7 @@ export interface Note {
   selectorId?: number
   tags: string[]
   isPinned: boolean
+  autoTitle?: boolean
   isFloating: boolean
   floatingWindowId?: string
 }
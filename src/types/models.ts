@@ -5,6 +5,7 @@ export interface Note {
   category_id?: number;
   selector_id?: number;
   is_pinned: boolean;
+  auto_title: boolean; // Title follows the first line of content
   tags: Tag[];
   created_at: string;
   updated_at: string;
@@ -24,11 +25,12 @@ export interface Tag {
 }
 
 export interface CreateNoteRequest {
-  title: string;
+  title: string; // Empty to derive it from the content
   content: string;
   category_id?: number;
   selector_id?: number;
   tags: string[];
+  auto_title?: boolean;
 }
 
 export interface UpdateNoteRequest {
@@ -37,18 +39,51 @@ export interface UpdateNoteRequest {
   content: string;
   category_id?: number;
   selector_id?: number;
-  tags: string[];
+  tags?: string[]; // Omit to keep the note's tags
   is_pinned: boolean;
+  auto_title?: boolean; // Omit to keep the note's mode; editing the title ends auto mode
 }
 
 export interface SearchOptions {
   query: string;
   category_id?: number;
+  include_subcategories?: boolean;
   tags?: string[];
+  selector_ids?: number[];
+  without_selector?: boolean;
   limit?: number;
   offset?: number;
 }
 
+export interface SelectorGroup {
+  selector_id: number | null;
+  count: number;
+  notes: Note[];
+}
+
 export interface SyncSettings {
   icloud_sync_enabled: boolean;
-}
\ No newline at end of file
+}
+
+export interface SyncStatus {
+  enabled: boolean;
+  location: 'local' | 'icloud';
+  database_path: string;
+  database_size: number;
+  wal_size: number;
+  target_directory: string;
+  target_writable: boolean;
+  last_sync: string | null;
+  last_error: string | null;
+  folder_sync_enabled: boolean;
+  folder_sync_path: string | null;
+}
+export interface FloatingWindowInfo {
+  label: string;
+  note_id: number;
+  title: string;
+  always_on_top: boolean;
+  focused: boolean;
+}
+
+export type FloatingLayout = 'cascade' | 'tile';